@@ -0,0 +1,200 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use rand::Rng;
+use rand::distributions::{Gamma, IndependentSample};
+
+use super::{betainc, linspace, Cdf, Pmf, Real};
+
+/// Represents a Beta distribution.
+///
+/// See http://en.wikipedia.org/wiki/Beta_distribution
+/// It is the conjugate prior of the probability of heads,
+/// so updating it with heads and tails is just addition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Beta {
+    alpha: f64,
+    beta: f64,
+}
+
+impl Default for Beta {
+    /// The uniform distribution, Beta(1, 1).
+    fn default() -> Beta {
+        Beta::new(1.0, 1.0)
+    }
+}
+
+impl Beta {
+    /// Initializes a Beta distribution.
+    /// panics if a parameter is not positive.
+    pub fn new(alpha: f64, beta: f64) -> Beta {
+        assert!(alpha > 0.0 && beta > 0.0, "Beta: the parameters must be positive");
+        Beta { alpha, beta }
+    }
+
+    /// Gets alpha, the prior plus the heads seen so far.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Gets beta, the prior plus the tails seen so far.
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+
+    /// Updates a Beta distribution.
+    ///     data: pair of int (heads, tails)
+    pub fn update(&mut self, (heads, tails): (u32, u32)) {
+        self.alpha += f64::from(heads);
+        self.beta += f64::from(tails);
+    }
+
+    /// Computes the mean of this distribution.
+    pub fn mean(&self) -> f64 {
+        self.alpha / (self.alpha + self.beta)
+    }
+
+    /// Computes the mode of this distribution, the x with the largest density.
+    ///     If a parameter is below 1 the density is infinite at that end.
+    ///     The mode is not unique when both are 1 or less:
+    ///     for Beta(1, 1) every x is a mode, this gives 0.5,
+    ///     otherwise this gives the end with the smaller parameter, 0 for a tie.
+    pub fn mode(&self) -> f64 {
+        match (self.alpha <= 1.0, self.beta <= 1.0) {
+            (true, true) if self.alpha == 1.0 && self.beta == 1.0 => 0.5,
+            (true, true) => if self.alpha <= self.beta { 0.0 } else { 1.0 },
+            (true, false) => 0.0,
+            (false, true) => 1.0,
+            (false, false) => (self.alpha - 1.0) / (self.alpha + self.beta - 2.0),
+        }
+    }
+
+    /// Generates a random variate from this distribution.
+    ///     rng: the source of randomness, seed it for repeatable results
+    pub fn random<R: Rng>(&self, rng: &mut R) -> f64 {
+        let x = Gamma::new(self.alpha, 1.0).ind_sample(rng);
+        let y = Gamma::new(self.beta, 1.0).ind_sample(rng);
+        x / (x + y)
+    }
+
+    /// Generates a random sample from this distribution.
+    ///     n: int sample size
+    pub fn sample<R: Rng>(&self, n: usize, rng: &mut R) -> Vec<f64> {
+        (0..n).map(|_| self.random(rng)).collect()
+    }
+
+    /// Evaluates the PDF at x, up to a constant that does not depend on x,
+    ///     x^(alpha - 1) (1 - x)^(beta - 1).
+    pub fn eval_pdf(&self, x: f64) -> f64 {
+        x.powf(self.alpha - 1.0) * (1.0 - x).powf(self.beta - 1.0)
+    }
+
+    /// Returns a Pmf of this distribution, evaluated at steps equally-spaced
+    ///     points from 0 to 1. The densities are scaled by the largest in log space,
+    ///     so a posterior after many trials does not underflow.
+    ///     If a parameter is below 1 the density is infinite at that end,
+    ///     so the Pmf comes from the Cdf instead, each point gets the probability
+    ///     of the step below it.
+    ///     Returns: normalized Pmf
+    pub fn make_pmf(&self, steps: usize) -> Pmf<Real> {
+        assert!(steps >= 2, "Beta make_pmf: needs at least 2 steps");
+        let xs = linspace(0.0, 1.0, steps);
+        let mut pmf = Pmf::new();
+        if self.alpha < 1.0 || self.beta < 1.0 {
+            let mut last = 0.0;
+            for x in xs {
+                let p = betainc(self.alpha, self.beta, x);
+                pmf.set(Real::new(x), p - last);
+                last = p;
+            }
+        } else {
+            // x^(a - 1), in log space, taking 0^0 to be 1
+            let log_pow = |x: f64, a: f64| if a == 1.0 { 0.0 } else { (a - 1.0) * x.ln() };
+            let logs: Vec<f64> = xs.iter()
+                .map(|&x| log_pow(x, self.alpha) + log_pow(1.0 - x, self.beta))
+                .collect();
+            let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            for (&x, &log) in xs.iter().zip(logs.iter()) {
+                pmf.set(Real::new(x), (log - max).exp());
+            }
+        }
+        pmf.normalize(1.0);
+        pmf
+    }
+
+    /// Returns the CDF of this distribution, evaluated at steps equally-spaced
+    ///     points from 0 to 1.
+    pub fn make_cdf(&self, steps: usize) -> Cdf<Real> {
+        assert!(steps >= 2, "Beta make_cdf: needs at least 2 steps");
+        let mut last = 0.0;
+        let items: Vec<(Real, f64)> = linspace(0.0, 1.0, steps)
+            .into_iter()
+            .map(|x| {
+                let p = betainc(self.alpha, self.beta, x);
+                let step = p - last;
+                last = p;
+                (Real::new(x), step)
+            })
+            .collect();
+        Cdf::from_sorted_items(items).expect("Beta make_cdf: the probabilities add up to 1")
+    }
+}
+
+#[cfg(test)]
+mod tests_beta {
+    use super::*;
+    use rand::{SeedableRng, XorShiftRng};
+    #[test]
+    fn beta_update() {
+        let mut beta = Beta::default();
+        beta.update((140, 110));
+        assert_eq!((beta.alpha(), beta.beta()), (141.0, 111.0));
+        assert_ulps_eq!{beta.mean(), 141.0 / 252.0, max_ulps = 4}
+        assert_ulps_eq!{beta.mode(), 0.56, max_ulps = 4}
+        assert_eq!(Beta::new(1.0, 1.0).mode(), 0.5);
+        assert_eq!(Beta::new(0.5, 2.0).mode(), 0.0);
+        assert_eq!(Beta::new(2.0, 0.5).mode(), 1.0);
+        // U shaped, the density is lowest in the middle
+        assert_eq!(Beta::new(0.5, 0.5).mode(), 0.0);
+        assert_eq!(Beta::new(0.5, 0.3).mode(), 1.0);
+        assert_eq!(Beta::new(1.0, 0.5).mode(), 1.0);
+        assert!(Beta::new(0.5, 0.5).eval_pdf(0.01) > Beta::new(0.5, 0.5).eval_pdf(0.5));
+        assert_eq!(Beta::new(4.0, 2.0).eval_pdf(0.5), 0.0625);
+    }
+    #[test]
+    fn beta_make_pmf_cdf() {
+        let beta = Beta::new(4.0, 4.0);
+        let pmf = beta.make_pmf(101);
+        assert_ulps_eq!{pmf.mean(), 0.5, max_ulps = 16}
+        assert_eq!(pmf.maximum_likelihood(), &Real::new(0.5));
+        assert_eq!(pmf.prob(&Real::new(0.0), 1.0), 0.0);
+        let cdf = beta.make_cdf(101);
+        assert_relative_eq!{cdf.prob(Real::new(0.5)), 0.5, max_relative = 1e-12};
+        // far too many trials to evaluate the density directly
+        let pmf = Beta::new(3001.0, 7001.0).make_pmf(101);
+        assert_eq!(pmf.maximum_likelihood(), &Real::new(0.3));
+    }
+    #[test]
+    fn beta_small_params() {
+        // the density is infinite at 0, but the Pmf is not
+        let beta = Beta::new(0.5, 3.0);
+        let pmf = beta.make_pmf(11);
+        assert_eq!(pmf.prob(&Real::new(0.0), 1.0), 0.0);
+        assert_relative_eq!{pmf.prob(&Real::new(0.1), 0.0), betainc(0.5, 3.0, 0.1), max_relative = 1e-12};
+        assert_eq!(pmf.maximum_likelihood(), &Real::new(0.1));
+        assert_ulps_eq!{pmf.total(), 1.0, max_ulps = 4}
+    }
+    #[test]
+    fn beta_random() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let beta = Beta::new(20.0, 80.0);
+        let sample = beta.sample(2000, &mut rng);
+        assert!(sample.iter().all(|&x| 0.0 < x && x < 1.0));
+        let mean = sample.iter().sum::<f64>() / sample.len() as f64;
+        assert_relative_eq!{mean, 0.2, epsilon = 0.01};
+    }
+}
@@ -1,36 +1,172 @@
-
 use std::cmp::Eq;
 use std::hash::Hash;
-use std::cmp::Ord;
+use std::cmp::{Ord, Ordering};
+use std::error::Error;
+use std::fmt;
 use rand::{thread_rng, Rng};
 
+/// The ways building a Cdf can fail.
+///
+/// Values only need to be `PartialOrd`, so that `Cdf<f64>` works, but then
+/// a NaN can sneak in. Rather than sorting it to some arbitrary place we refuse it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CdfError {
+    /// A value could not be compared with the others, e.g. a NaN.
+    Unordered,
+    /// A frequency was NaN.
+    NanFrequency,
+    /// A frequency was negative.
+    NegativeFrequency,
+    /// The frequencies add up to zero, so there is nothing to normalize.
+    ZeroTotal,
+    /// Values given to `from_sorted` or `from_sorted_items` were out of order.
+    Unsorted,
+}
+
+impl fmt::Display for CdfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CdfError::Unordered => write!(f, "Cdf: values can not be ordered (NaN?)"),
+            CdfError::NanFrequency => write!(f, "Cdf: frequency is NaN"),
+            CdfError::NegativeFrequency => write!(f, "Cdf: frequency is negative"),
+            CdfError::ZeroTotal => write!(f, "Cdf: frequencies add up to zero"),
+            CdfError::Unsorted => write!(f, "Cdf: values are not sorted"),
+        }
+    }
+}
+
+impl Error for CdfError {}
+
+/// Compares two values, or fails if they can not be ordered.
+fn checked_cmp<V: PartialOrd>(a: &V, b: &V) -> Result<Ordering, CdfError> {
+    a.partial_cmp(b).ok_or(CdfError::Unordered)
+}
+
+/// Checks that every value can be compared with itself, which rules out a NaN.
+/// Do this before sorting, a comparator that fails part way is not a total order.
+fn check_ordered<'a, V, I>(seq: I) -> Result<(), CdfError>
+    where V: PartialOrd + 'a,
+          I: IntoIterator<Item = &'a V>
+{
+    for x in seq {
+        checked_cmp(x, x)?;
+    }
+    Ok(())
+}
+
 /// Represents a cumulative distribution function.
 /// Attributes:
 ///     xs: sequence of values
 ///     ps: sequence of probabilities
 ///     label: string used as a graph label.
 #[derive(Clone, PartialEq)]
-pub struct Cdf<V: Eq + Clone + Ord> {
+pub struct Cdf<V: Clone + PartialOrd> {
     xs: Vec<V>,
     ps: Vec<f64>,
 }
 
-impl<V: Eq + Clone + Ord> Cdf<V> {
+impl<V: Clone + PartialOrd> Cdf<V> {
+    /// Makes a cdf from an unsorted sequence of values.
+    ///
+    /// seq: slice of values
+    ///
+    /// returns: Cdf object, or an error if the values can not be ordered
+    pub fn from_list(seq: &[V]) -> Result<Cdf<V>, CdfError> {
+        check_ordered(seq)?;
+        let mut xs = seq.to_vec();
+        // from_sorted still checks the order, in case a type is only partially ordered
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        Cdf::from_sorted(xs)
+    }
+
+    /// Makes a cdf from an unsorted sequence of (value, frequency) pairs.
+    ///
+    /// items: iter of (value, frequency) pairs
+    ///
+    /// returns: Cdf object, or an error if the values can not be ordered
+    pub fn from_items<I: IntoIterator<Item = (V, f64)>>(items: I) -> Result<Cdf<V>, CdfError> {
+        let mut items: Vec<_> = items.into_iter().collect();
+        check_ordered(items.iter().map(|item| &item.0))?;
+        items.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        Cdf::from_sorted_items(items)
+    }
+
+    /// Makes a cdf from a sequence of values that is already sorted.
+    /// This is O(n), so it is the way to go for large samples.
+    ///
+    /// seq: iter of values in increasing order
+    ///
+    /// returns: Cdf object, or an error if the values are not sorted
+    pub fn from_sorted<I: IntoIterator<Item = V>>(seq: I) -> Result<Cdf<V>, CdfError> {
+        Cdf::from_sorted_items(seq.into_iter().map(|x| (x, 1.0)))
+    }
+
+    /// Makes a cdf from (value, frequency) pairs that are already sorted by value.
+    /// Repeated values are merged. This is O(n).
+    ///
+    /// items: iter of (value, frequency) pairs in increasing order of value
+    ///
+    /// returns: Cdf object, or an error if the values are not sorted,
+    ///     a frequency is negative or they add up to zero
+    pub fn from_sorted_items<I>(items: I) -> Result<Cdf<V>, CdfError>
+        where I: IntoIterator<Item = (V, f64)>
+    {
+        let mut xs: Vec<V> = Vec::new();
+        let mut ps: Vec<f64> = Vec::new();
+        let mut total = 0.0;
+        for (x, freq) in items {
+            if freq.is_nan() {
+                return Err(CdfError::NanFrequency);
+            }
+            if freq < 0.0 {
+                return Err(CdfError::NegativeFrequency);
+            }
+            // a NaN is not even equal to itself
+            checked_cmp(&x, &x)?;
+            total += freq;
+            let repeat = match xs.last() {
+                Some(last) => {
+                    match checked_cmp(last, &x)? {
+                        Ordering::Greater => return Err(CdfError::Unsorted),
+                        Ordering::Equal => true,
+                        Ordering::Less => false,
+                    }
+                }
+                None => false,
+            };
+            if repeat {
+                *ps.last_mut().unwrap() = total;
+            } else {
+                xs.push(x);
+                ps.push(total);
+            }
+        }
+        if !ps.is_empty() && total == 0.0 {
+            return Err(CdfError::ZeroTotal);
+        }
+        for p in ps.iter_mut() {
+            *p /= total;
+        }
+        Ok(Cdf { xs, ps })
+    }
+
     /// Returns CDF(x), the probability that corresponds to value x.
     ///
     /// Args:
     ///     x: number
     ///
     /// Returns:
-    ///     float probability
+    ///     float probability, NaN if x can not be compared (is NaN)
     pub fn prob(&self, x: V) -> f64 {
-        if x < *self.xs.first().unwrap() {
+        if checked_cmp(&x, &x).is_err() {
+            return f64::NAN;
+        }
+        // the number of values <= x
+        let index = self.xs.partition_point(|v| *v <= x);
+        if index == 0 {
             0.0
-        } else if x > *self.xs.last().unwrap() {
-            1.0
         } else {
-            let index = self.xs.binary_search(&x);
-            self.ps[index.unwrap_or_else(|x| x)]
+            self.ps[index - 1]
         }
     }
 
@@ -38,7 +174,7 @@ impl<V: Eq + Clone + Ord> Cdf<V> {
     /// Args:
     ///     p: number in the range [0, 1]
     pub fn value(&self, p: f64) -> V {
-        if p < 0.0 || p > 1.0 {
+        if !(0.0..=1.0).contains(&p) {
             panic!("Probability p must be in range [0, 1]")
         }
         let index = self.ps
@@ -91,6 +227,128 @@ impl<V: Eq + Clone + Ord> Cdf<V> {
         }
         cdf
     }
+
+    /// Walks the values of both Cdfs in order.
+    ///
+    /// returns: for each distinct value, (value, CDF of self, CDF of other)
+    fn merged_steps<'a>(&'a self, other: &'a Cdf<V>) -> Vec<(&'a V, f64, f64)> {
+        let mut steps = Vec::with_capacity(self.xs.len() + other.xs.len());
+        let (mut i, mut j) = (0, 0);
+        let (mut p1, mut p2) = (0.0, 0.0);
+        while i < self.xs.len() || j < other.xs.len() {
+            let ord = if i == self.xs.len() {
+                Ordering::Greater
+            } else if j == other.xs.len() {
+                Ordering::Less
+            } else {
+                self.xs[i].partial_cmp(&other.xs[j]).expect("Couldn't compare values")
+            };
+            let x = if ord == Ordering::Greater {
+                &other.xs[j]
+            } else {
+                &self.xs[i]
+            };
+            if ord != Ordering::Greater {
+                p1 = self.ps[i];
+                i += 1;
+            }
+            if ord != Ordering::Less {
+                p2 = other.ps[j];
+                j += 1;
+            }
+            steps.push((x, p1, p2));
+        }
+        steps
+    }
+
+    /// Computes the Kolmogorov-Smirnov distance,
+    /// the largest vertical gap between this Cdf and other.
+    ///
+    /// other: Cdf to compare with
+    ///
+    /// returns: float in [0, 1]
+    pub fn ks_distance(&self, other: &Cdf<V>) -> f64 {
+        self.merged_steps(other)
+            .iter()
+            .fold(0.0, |d, &(_, p1, p2)| d.max((p1 - p2).abs()))
+    }
+
+    /// Computes the Kolmogorov-Smirnov test statistic, sqrt(n) * ks_distance.
+    ///
+    /// other: Cdf to compare with
+    /// n: effective sample size. For a sample of size n against an exact Cdf
+    ///     that is n, for two samples of sizes n and m it is n * m / (n + m).
+    ///
+    /// returns: float statistic, see `kolmogorov_p_value`
+    pub fn ks_statistic(&self, other: &Cdf<V>, n: f64) -> f64 {
+        n.sqrt() * self.ks_distance(other)
+    }
+
+    /// Checks for first-order stochastic dominance,
+    /// that is CDF(x) of self <= CDF(x) of other for every x.
+    /// So values drawn from self tend to be larger.
+    ///
+    /// other: Cdf to compare with
+    ///
+    /// returns: bool
+    pub fn dominates(&self, other: &Cdf<V>) -> bool {
+        self.merged_steps(other).iter().all(|&(_, p1, p2)| p1 <= p2)
+    }
+
+    /// Pairs up the quantiles of this Cdf and other, for a Q-Q plot.
+    ///
+    /// n: number of quantiles, taken at probabilities (i + 0.5) / n
+    ///
+    /// returns: Vec of (value from self, value from other)
+    pub fn qq_pairs(&self, other: &Cdf<V>, n: usize) -> Vec<(V, V)> {
+        (0..n)
+            .map(|i| (i as f64 + 0.5) / n as f64)
+            .map(|p| (self.value(p), other.value(p)))
+            .collect()
+    }
+}
+
+impl<V: Copy + PartialOrd + Into<f64>> Cdf<V> {
+    /// Computes the Wasserstein-1 (earth mover's) distance,
+    /// the area between this Cdf and other.
+    ///
+    /// other: Cdf to compare with
+    ///
+    /// returns: float distance in the units of the values
+    pub fn wasserstein(&self, other: &Cdf<V>) -> f64 {
+        let steps = self.merged_steps(other);
+        steps.iter()
+            .zip(steps.iter().skip(1))
+            .fold(0.0, |s, (&(&x1, p1, p2), &(&x2, _, _))| {
+                s + (p1 - p2).abs() * (x2.into() - x1.into())
+            })
+    }
+}
+
+/// Computes the p-value for a Kolmogorov-Smirnov statistic,
+/// using the asymptotic Kolmogorov distribution.
+/// For discrete distributions this is conservative.
+///
+/// statistic: as returned by `Cdf::ks_statistic`
+///
+/// returns: float probability of a statistic at least this big if the Cdfs match
+pub fn kolmogorov_p_value(statistic: f64) -> f64 {
+    if statistic < 0.2 {
+        // the series converges slowly here, and the answer is 1 to many digits
+        return 1.0;
+    }
+    let a2 = -2.0 * statistic * statistic;
+    let mut sum = 0.0;
+    let mut sign = 2.0;
+    for k in 1..101 {
+        let term = sign * (a2 * f64::from(k * k)).exp();
+        sum += term;
+        if term.abs() <= 1e-16 * sum.abs() {
+            break;
+        }
+        sign = -sign;
+    }
+    sum.clamp(0.0, 1.0)
 }
 
 impl<'a, V: Eq + Hash + Clone + Ord> From<&'a super::pmf::Pmf<V>> for Cdf<V> {
@@ -108,3 +366,99 @@ impl<'a, V: Eq + Hash + Clone + Ord> From<&'a super::pmf::Pmf<V>> for Cdf<V> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests_cdf {
+    use super::*;
+    #[test]
+    fn cdf_from_list() {
+        let cdf = Cdf::from_list(&[3, 1, 2, 2]).unwrap();
+        assert_ulps_eq!{cdf.prob(1), 0.25, max_ulps = 4}
+        assert_ulps_eq!{cdf.prob(2), 0.75, max_ulps = 4}
+        assert_ulps_eq!{cdf.prob(3), 1.0, max_ulps = 4}
+        assert_eq!{cdf.percentile(50.0), 2}
+    }
+    #[test]
+    fn cdf_from_items() {
+        let cdf = Cdf::from_items(vec![(2, 1.0), (1, 1.0), (3, 2.0), (1, 0.0)]).unwrap();
+        assert_ulps_eq!{cdf.prob(1), 0.25, max_ulps = 4}
+        assert_ulps_eq!{cdf.prob(2), 0.5, max_ulps = 4}
+        assert_ulps_eq!{cdf.prob(3), 1.0, max_ulps = 4}
+    }
+    #[test]
+    fn cdf_from_sorted_matches_pmf() {
+        let mut pmf = ::Pmf::new();
+        for &x in &[1, 2, 2, 3, 3, 3] {
+            pmf.incr(x, 1.0);
+        }
+        pmf.normalize(1.0);
+        let from_pmf = pmf.make_cdf();
+        let from_sorted = Cdf::from_sorted(vec![1, 2, 2, 3, 3, 3]).unwrap();
+        for x in 1..4 {
+            assert_ulps_eq!{from_pmf.prob(x), from_sorted.prob(x), max_ulps = 4}
+        }
+    }
+    #[test]
+    fn cdf_from_sorted_unsorted() {
+        assert_eq!{Cdf::from_sorted(vec![1, 3, 2]).err(), Some(CdfError::Unsorted)}
+    }
+    #[test]
+    fn cdf_prob_between_values() {
+        let cdf = Cdf::from_list(&[1, 3]).unwrap();
+        assert_ulps_eq!{cdf.prob(0), 0.0, max_ulps = 4}
+        assert_ulps_eq!{cdf.prob(2), 0.5, max_ulps = 4}
+        assert_ulps_eq!{cdf.prob(4), 1.0, max_ulps = 4}
+    }
+    #[test]
+    fn cdf_of_floats() {
+        let cdf = Cdf::from_list(&[0.3, 0.1, 0.2, 0.4]).unwrap();
+        assert_ulps_eq!{cdf.prob(0.25), 0.5, max_ulps = 4}
+        assert_ulps_eq!{cdf.value(0.75), 0.3, max_ulps = 4}
+        assert!{cdf.prob(f64::NAN).is_nan()}
+    }
+    #[test]
+    fn cdf_of_floats_with_nan() {
+        assert_eq!{Cdf::from_list(&[0.3, f64::NAN, 0.2]).err(), Some(CdfError::Unordered)}
+        assert_eq!{Cdf::from_list(&[f64::NAN]).err(), Some(CdfError::Unordered)}
+        assert_eq!{Cdf::from_sorted(vec![0.1, f64::NAN]).err(), Some(CdfError::Unordered)}
+        assert_eq!{Cdf::from_items(vec![(0.1, f64::NAN)]).err(), Some(CdfError::NanFrequency)}
+    }
+    #[test]
+    fn cdf_bad_frequencies() {
+        // a negative frequency would make the probabilities go down
+        let items = vec![(1, 1.0), (2, -0.5), (3, 1.0)];
+        assert_eq!{Cdf::from_sorted_items(items).err(), Some(CdfError::NegativeFrequency)}
+        // every probability would be 0/0
+        let items = vec![(1, 0.0), (2, 0.0)];
+        assert_eq!{Cdf::from_sorted_items(items).err(), Some(CdfError::ZeroTotal)}
+        assert_eq!{Cdf::from_items(vec![(2, 0.0), (1, 0.0)]).err(), Some(CdfError::ZeroTotal)}
+    }
+    #[test]
+    fn cdf_compare_shifted() {
+        let low = Cdf::from_list(&[1, 2, 3, 4]).unwrap();
+        let high = Cdf::from_list(&[2, 3, 4, 5]).unwrap();
+        assert_ulps_eq!{low.ks_distance(&high), 0.25, max_ulps = 4}
+        assert_ulps_eq!{low.ks_distance(&low), 0.0, max_ulps = 4}
+        assert_ulps_eq!{low.wasserstein(&high), 1.0, max_ulps = 4}
+        assert_ulps_eq!{high.wasserstein(&low), 1.0, max_ulps = 4}
+        assert!{high.dominates(&low)}
+        assert!{!low.dominates(&high)}
+        assert_eq!{low.qq_pairs(&high, 4), vec![(1, 2), (2, 3), (3, 4), (4, 5)]}
+    }
+    #[test]
+    fn cdf_compare_crossing() {
+        let narrow = Cdf::from_list(&[2.0, 3.0]).unwrap();
+        let wide = Cdf::from_list(&[1.0, 4.0]).unwrap();
+        assert!{!narrow.dominates(&wide)}
+        assert!{!wide.dominates(&narrow)}
+        assert_ulps_eq!{narrow.ks_distance(&wide), 0.5, max_ulps = 4}
+        assert_ulps_eq!{narrow.wasserstein(&wide), 1.0, max_ulps = 4}
+    }
+    #[test]
+    fn kolmogorov_p_value_known() {
+        // the 5% and 1% critical values of the Kolmogorov distribution
+        assert_relative_eq!{kolmogorov_p_value(1.3581), 0.05, epsilon = 0.0001}
+        assert_relative_eq!{kolmogorov_p_value(1.6276), 0.01, epsilon = 0.0001}
+        assert_ulps_eq!{kolmogorov_p_value(0.0), 1.0, max_ulps = 4}
+    }
+}
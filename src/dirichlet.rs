@@ -0,0 +1,206 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use rand::Rng;
+use rand::distributions::{Gamma, IndependentSample};
+
+use super::{linspace, Beta, Pmf, Real};
+
+/// Represents a Dirichlet distribution.
+///
+/// See http://en.wikipedia.org/wiki/Dirichlet_distribution
+/// It is the conjugate prior of the probabilities of n categories,
+/// so updating it with the counts of each category is just addition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dirichlet {
+    params: Vec<f64>,
+}
+
+impl Dirichlet {
+    /// Initializes a Dirichlet distribution.
+    ///     n: number of dimensions
+    ///     conc: concentration parameter (smaller yields more concentration)
+    /// panics if conc is not positive and finite.
+    pub fn new(n: usize, conc: f64) -> Dirichlet {
+        Dirichlet::from_params(vec![conc; n])
+    }
+
+    /// Initializes a Dirichlet distribution with a parameter for each dimension.
+    /// panics if a parameter is not positive and finite.
+    pub fn from_params(params: Vec<f64>) -> Dirichlet {
+        assert!(params.iter().all(|&param| param > 0.0 && param.is_finite()),
+                "Dirichlet: the parameters must be positive and finite");
+        Dirichlet { params }
+    }
+
+    /// Gets the parameters, the prior plus the counts seen so far.
+    pub fn params(&self) -> &[f64] {
+        &self.params
+    }
+
+    /// Updates a Dirichlet distribution.
+    ///     data: sequence of observations, in order corresponding to params
+    ///     the first data.len() categories are updated.
+    /// panics if there is more data than categories.
+    pub fn update(&mut self, data: &[u32]) {
+        assert!(data.len() <= self.params.len(),
+                "Dirichlet update: more data than categories");
+        for (param, &count) in self.params.iter_mut().zip(data) {
+            *param += f64::from(count);
+        }
+    }
+
+    /// Generates a random variate from this distribution.
+    ///     rng: the source of randomness, seed it for repeatable results
+    ///     Returns: normalized vector of probabilities
+    pub fn random<R: Rng>(&self, rng: &mut R) -> Vec<f64> {
+        let mut p: Vec<f64> = self.params
+            .iter()
+            .map(|&param| Gamma::new(param, 1.0).ind_sample(rng))
+            .collect();
+        let total = p.iter().fold(0.0, |s, x| s + x);
+        for x in &mut p {
+            *x /= total;
+        }
+        p
+    }
+
+    /// Computes the likelihood of the data, using one random vector of probabilities.
+    ///     Selects a random vector of probabilities from this distribution.
+    ///     Returns: float probability
+    pub fn likelihood<R: Rng>(&self, data: &[u32], rng: &mut R) -> f64 {
+        self.log_likelihood(data, rng).exp()
+    }
+
+    /// Computes the log likelihood of the data, like `likelihood`.
+    ///     Returns: float log probability, -inf if there is more data than categories
+    pub fn log_likelihood<R: Rng>(&self, data: &[u32], rng: &mut R) -> f64 {
+        if data.len() > self.params.len() {
+            return f64::NEG_INFINITY;
+        }
+        self.random(rng)
+            .iter()
+            .zip(data)
+            .fold(0.0, |s, (p, &x)| s + f64::from(x) * p.ln())
+    }
+
+    /// Computes the mean probability of each category.
+    pub fn mean(&self) -> Vec<f64> {
+        let alpha0 = self.params.iter().fold(0.0, |s, x| s + x);
+        self.params.iter().map(|param| param / alpha0).collect()
+    }
+
+    /// Computes the marginal distribution of the ith element,
+    ///     a beta distribution.
+    pub fn marginal_beta(&self, i: usize) -> Beta {
+        let alpha0 = self.params.iter().fold(0.0, |s, x| s + x);
+        let alpha = self.params[i];
+        Beta::new(alpha, alpha0 - alpha)
+    }
+
+    /// Computes the marginal distribution of the ith element,
+    ///     a beta distribution, evaluated on a grid of steps points from 0 to 1.
+    ///     If a parameter is below 1 the density is infinite at that end,
+    ///     so that end point gets the density half a step in.
+    ///     `marginal_beta(i).make_pmf(steps)` gives that end point no probability instead.
+    ///     Returns: normalized Pmf
+    pub fn marginal_pmf(&self, i: usize, steps: usize) -> Pmf<Real> {
+        let marginal = self.marginal_beta(i);
+        let (alpha, beta) = (marginal.alpha(), marginal.beta());
+        assert!(steps >= 2, "marginal_pmf: needs at least 2 steps");
+        let half = 0.5 / (steps - 1) as f64;
+        // x^(a - 1), in log space, taking 0^0 to be 1
+        let log_pow = |x: f64, a: f64| if a == 1.0 { 0.0 } else { (a - 1.0) * x.ln() };
+        let xs = linspace(0.0, 1.0, steps);
+        let logs: Vec<f64> = xs.iter()
+            .map(|&x| {
+                let x = if (x == 0.0 && alpha < 1.0) || (x == 1.0 && beta < 1.0) {
+                    x.clamp(half, 1.0 - half)
+                } else {
+                    x
+                };
+                log_pow(x, alpha) + log_pow(1.0 - x, beta)
+            })
+            .collect();
+        let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let mut pmf = Pmf::new();
+        for (&x, &log) in xs.iter().zip(logs.iter()) {
+            pmf.set(Real::new(x), (log - max).exp());
+        }
+        pmf.normalize(1.0);
+        pmf
+    }
+
+    /// Makes the predictive distribution of the next category,
+    ///     the mean probability of each.
+    ///     Returns: Pmf over the indexes of the categories
+    pub fn predictive_pmf(&self) -> Pmf<usize> {
+        let mut pmf = Pmf::new();
+        for (i, p) in self.mean().into_iter().enumerate() {
+            pmf.set(i, p);
+        }
+        pmf
+    }
+}
+
+#[cfg(test)]
+mod tests_dirichlet {
+    use super::*;
+    use rand::{SeedableRng, XorShiftRng};
+    #[test]
+    fn dirichlet_update_mean() {
+        let mut dirichlet = Dirichlet::new(3, 1.0);
+        dirichlet.update(&[3, 2]);
+        assert_eq!(dirichlet.params(), &[4.0, 3.0, 1.0]);
+        assert_ulps_eq!{dirichlet.predictive_pmf().prob(&0, 0.0), 0.5, max_ulps = 4}
+        let marginal = dirichlet.marginal_pmf(0, 101);
+        // Beta(4, 4) is symmetric about 0.5
+        assert_ulps_eq!{marginal.mean(), 0.5, max_ulps = 16}
+        assert_eq!(marginal.maximum_likelihood(), &Real::new(0.5));
+        assert_eq!(marginal.prob(&Real::new(0.0), 1.0), 0.0);
+    }
+    #[test]
+    fn dirichlet_random() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let dirichlet = Dirichlet::from_params(vec![20.0, 50.0, 30.0]);
+        let n = 2000;
+        let mut mean = [0.0; 3];
+        for _ in 0..n {
+            let p = dirichlet.random(&mut rng);
+            assert_relative_eq!{p.iter().sum::<f64>(), 1.0, max_relative = 1e-12};
+            for (m, x) in mean.iter_mut().zip(p) {
+                *m += x / f64::from(n);
+            }
+        }
+        assert_relative_eq!{mean[1], 0.5, epsilon = 0.01};
+        // the same seed gives the same draws
+        let mut a = XorShiftRng::from_seed([5, 6, 7, 8]);
+        let mut b = XorShiftRng::from_seed([5, 6, 7, 8]);
+        assert_eq!(dirichlet.random(&mut a), dirichlet.random(&mut b));
+        assert_eq!(dirichlet.log_likelihood(&[1, 1, 1, 1], &mut a), f64::NEG_INFINITY);
+    }
+    #[test]
+    fn dirichlet_marginal_small_params() {
+        // the density is infinite at 0, but the Pmf is not
+        let dirichlet = Dirichlet::from_params(vec![0.5, 3.0]);
+        let marginal = dirichlet.marginal_pmf(0, 11);
+        assert!(marginal.prob(&Real::new(0.0), 0.0).is_finite());
+        assert_eq!(marginal.maximum_likelihood(), &Real::new(0.0));
+        assert_eq!(dirichlet.marginal_beta(0), Beta::new(0.5, 3.0));
+        assert_ulps_eq!{marginal.total(), 1.0, max_ulps = 4}
+    }
+    #[test]
+    #[should_panic(expected = "the parameters must be positive and finite")]
+    fn dirichlet_zero_conc() {
+        Dirichlet::new(3, 0.0);
+    }
+    #[test]
+    #[should_panic(expected = "the parameters must be positive and finite")]
+    fn dirichlet_bad_params() {
+        Dirichlet::from_params(vec![1.0, -2.0, f64::NAN]);
+    }
+}
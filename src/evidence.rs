@@ -0,0 +1,171 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use std::cmp::Eq;
+use std::hash::Hash;
+
+use super::{Pmf, Suite};
+
+/// Computes the Bayes factor in favor of suite a over suite b,
+/// the ratio of the marginal likelihoods of the same data.
+/// Neither suite is modified.
+/// The ratio is taken in log space, so a big dataset does not underflow.
+///
+/// a, b: the competing suites, they can have different types of hypotheses
+/// dataset: a sequence of data, it is iterated once for each suite
+///
+/// returns: float likelihood ratio, > 1 means the data favor a
+pub fn bayes_factor<'a, D, V, W, A, B, I>(a: &A, b: &B, dataset: I) -> f64
+    where D: 'a,
+          V: Eq + Hash + Clone,
+          W: Eq + Hash + Clone,
+          A: Suite<D, V>,
+          B: Suite<D, W>,
+          I: Iterator<Item = &'a D> + Clone
+{
+    (a.log_evidence(dataset.clone()) - b.log_evidence(dataset)).exp()
+}
+
+/// Keeps track of the evidence for several competing models of the same data.
+///
+/// Each model has a prior weight and the marginal likelihood of the data seen so far.
+/// Evidence can be added one datum at a time,
+/// using the normalizing constant that `Suite::update` returns,
+/// or for a whole dataset at once by passing `Suite::log_evidence`,
+/// or the `log_evidence` that `Suite::update_set` reports, to `observe_log`.
+/// The evidence is kept in log space, so a long run of data does not underflow.
+#[derive(Clone, PartialEq)]
+pub struct ModelComparison<K: Eq + Hash + Clone> {
+    models: Vec<(K, f64, f64)>,
+}
+
+impl<K: Eq + Hash + Clone> ModelComparison<K> {
+    pub fn new() -> ModelComparison<K> {
+        ModelComparison { models: Vec::new() }
+    }
+
+    /// Adds a model that has not seen any data yet.
+    /// Args:
+    ///     name: label for the model
+    ///     prior: prior weight, only the ratios between models matter
+    pub fn add_model(&mut self, name: K, prior: f64) {
+        self.models.push((name, prior, 0.0));
+    }
+
+    /// Multiplies in the marginal likelihood of more data under a model.
+    /// Args:
+    ///     name: label for the model
+    ///     evidence: the total probability of the data under the model
+    /// panics if there is no model with that name.
+    pub fn observe(&mut self, name: &K, evidence: f64) {
+        self.observe_log(name, evidence.ln());
+    }
+
+    /// Adds in the log of the marginal likelihood of more data under a model,
+    /// such as `UpdateStats::log_evidence`.
+    /// Args:
+    ///     name: label for the model
+    ///     log_evidence: the log of the total probability of the data under the model
+    /// panics if there is no model with that name.
+    pub fn observe_log(&mut self, name: &K, log_evidence: f64) {
+        let model = self.models
+            .iter_mut()
+            .find(|m| m.0 == *name)
+            .expect("observe: no model by that name");
+        model.2 += log_evidence;
+    }
+
+    /// Gets the log of the marginal likelihood of all the data observed under a model.
+    pub fn log_evidence(&self, name: &K) -> f64 {
+        self.models
+            .iter()
+            .find(|m| m.0 == *name)
+            .expect("evidence: no model by that name")
+            .2
+    }
+
+    /// Gets the marginal likelihood of all the data observed under a model,
+    /// this underflows to zero for big datasets, use `log_evidence` for those.
+    pub fn evidence(&self, name: &K) -> f64 {
+        self.log_evidence(name).exp()
+    }
+
+    /// Computes the Bayes factor in favor of model a over model b.
+    pub fn bayes_factor(&self, a: &K, b: &K) -> f64 {
+        (self.log_evidence(a) - self.log_evidence(b)).exp()
+    }
+
+    /// Computes the posterior odds in favor of model a over model b,
+    /// the prior odds times the Bayes factor.
+    pub fn posterior_odds(&self, a: &K, b: &K) -> f64 {
+        let prior = |name: &K| {
+            self.models
+                .iter()
+                .find(|m| m.0 == *name)
+                .expect("posterior_odds: no model by that name")
+                .1
+        };
+        prior(a) / prior(b) * self.bayes_factor(a, b)
+    }
+
+    /// Computes the posterior probability of each model.
+    /// The log evidence is shifted by the largest first, so it does not underflow.
+    /// Returns: normalized Pmf over the model names
+    pub fn posterior(&self) -> Pmf<K> {
+        let max = self.models.iter().fold(f64::NEG_INFINITY, |s, m| s.max(m.2));
+        let mut pmf = Pmf::new();
+        for &(ref name, prior, log_evidence) in &self.models {
+            pmf.set(name.clone(), prior * (log_evidence - max).exp());
+        }
+        pmf.normalize(1.0);
+        pmf
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for ModelComparison<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests_model_comparison {
+    use super::*;
+    #[test]
+    fn model_comparison_cookie() {
+        // the cookie problem from 2.2 as a comparison of two models
+        let mut models = ModelComparison::new();
+        models.add_model("Bowl 1", 1.0);
+        models.add_model("Bowl 2", 1.0);
+        models.observe(&"Bowl 1", 0.75);
+        models.observe(&"Bowl 2", 0.5);
+        assert_ulps_eq!{models.bayes_factor(&"Bowl 1", &"Bowl 2"), 1.5, max_ulps = 4}
+        assert_ulps_eq!{models.posterior().prob(&"Bowl 1", 0.0), 0.6, max_ulps = 4}
+        models.add_model("Bowl 3", 2.0);
+        models.observe(&"Bowl 3", 0.25);
+        assert_ulps_eq!{models.posterior_odds(&"Bowl 3", &"Bowl 2"), 1.0, max_ulps = 4}
+        assert_ulps_eq!{models.posterior().prob(&"Bowl 3", 0.0), 0.2857142857142857, max_ulps = 4}
+    }
+    #[test]
+    fn model_comparison_long_run() {
+        // 0.1^400 underflows, the log evidence does not
+        let mut models = ModelComparison::new();
+        models.add_model("a", 1.0);
+        models.add_model("b", 3.0);
+        for _ in 0..400 {
+            models.observe(&"a", 0.1);
+            models.observe(&"b", 0.05);
+        }
+        assert_eq!(models.evidence(&"a"), 0.0);
+        assert_relative_eq!{models.log_evidence(&"a"), 400.0 * 0.1f64.ln(), max_relative = 1e-12};
+        assert_relative_eq!{models.bayes_factor(&"a", &"b"), 2.0f64.powi(400), max_relative = 1e-9};
+        assert_relative_eq!{models.posterior_odds(&"b", &"a"), 3.0 * 0.5f64.powi(400), max_relative = 1e-9};
+        assert_eq!(models.posterior().prob(&"a", 0.0), 1.0);
+        models.observe_log(&"b", 400.0 * 2.0f64.ln());
+        assert_relative_eq!{models.posterior().prob(&"b", 0.0), 0.75, max_relative = 1e-9};
+    }
+}
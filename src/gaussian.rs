@@ -0,0 +1,323 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use std::f64::consts::{PI, SQRT_2};
+
+use super::{Pdf, Pmf, Real};
+
+/// Computes the error function and its complement,
+/// with W. J. Cody's rational approximations (netlib specfun calerf),
+/// good to about 1e-16 relative error.
+/// Returns: (erf(x), erfc(x))
+// the coefficients are copied as published
+#[allow(clippy::excessive_precision)]
+fn cody_erf(x: f64) -> (f64, f64) {
+    const A: [f64; 5] = [3.16112374387056560e00,
+                         1.13864154151050156e02,
+                         3.77485237685302021e02,
+                         3.20937758913846947e03,
+                         1.85777706184603153e-1];
+    const B: [f64; 4] = [2.36012909523441209e01,
+                         2.44024637934444173e02,
+                         1.28261652607737228e03,
+                         2.84423683343917062e03];
+    const C: [f64; 9] = [5.64188496988670089e-1,
+                         8.88314979438837594e00,
+                         6.61191906371416295e01,
+                         2.98635138197400131e02,
+                         8.81952221241769090e02,
+                         1.71204761263407058e03,
+                         2.05107837782607147e03,
+                         1.23033935479799725e03,
+                         2.15311535474403846e-8];
+    const D: [f64; 8] = [1.57449261107098347e01,
+                         1.17693950891312499e02,
+                         5.37181101862009858e02,
+                         1.62138957456669019e03,
+                         3.29079923573345963e03,
+                         4.36261909014324716e03,
+                         3.43936767414372164e03,
+                         1.23033935480374942e03];
+    const P: [f64; 6] = [3.05326634961232344e-1,
+                         3.60344899949804439e-1,
+                         1.25781726111229246e-1,
+                         1.60837851487422766e-2,
+                         6.58749161529837803e-4,
+                         1.63153871373020978e-2];
+    const Q: [f64; 5] = [2.56852019228982242e00,
+                         1.87295284992346725e00,
+                         5.27905102951428412e-1,
+                         6.05183413124413191e-2,
+                         2.33520497626869185e-3];
+    const SQRPI: f64 = 5.6418958354775628695e-1;
+
+    if x.is_nan() {
+        return (f64::NAN, f64::NAN);
+    }
+    let y = x.abs();
+    if y <= 0.46875 {
+        let ysq = y * y;
+        let mut xnum = A[4] * ysq;
+        let mut xden = ysq;
+        for i in 0..3 {
+            xnum = (xnum + A[i]) * ysq;
+            xden = (xden + B[i]) * ysq;
+        }
+        let erf = x * (xnum + A[3]) / (xden + B[3]);
+        return (erf, 1.0 - erf);
+    }
+    // erfc(y) = exp(-y^2) * r(y), with exp(-y^2) split in two to keep its accuracy
+    let r = if y <= 4.0 {
+        let mut xnum = C[8] * y;
+        let mut xden = y;
+        for i in 0..7 {
+            xnum = (xnum + C[i]) * y;
+            xden = (xden + D[i]) * y;
+        }
+        (xnum + C[7]) / (xden + D[7])
+    } else {
+        let ysq = 1.0 / (y * y);
+        let mut xnum = P[5] * ysq;
+        let mut xden = ysq;
+        for i in 0..4 {
+            xnum = (xnum + P[i]) * ysq;
+            xden = (xden + Q[i]) * ysq;
+        }
+        (SQRPI - ysq * (xnum + P[4]) / (xden + Q[4])) / y
+    };
+    let ysq = (y * 16.0).trunc() / 16.0;
+    let del = (y - ysq) * (y + ysq);
+    let erfc = (-ysq * ysq).exp() * (-del).exp() * r;
+    if x < 0.0 {
+        (erfc - 1.0, 2.0 - erfc)
+    } else {
+        (1.0 - erfc, erfc)
+    }
+}
+
+/// Computes the error function.
+pub fn erf(x: f64) -> f64 {
+    cody_erf(x).0
+}
+
+/// Computes the complementary error function, 1 - erf(x),
+/// without losing accuracy when erf(x) is close to 1.
+pub fn erfc(x: f64) -> f64 {
+    cody_erf(x).1
+}
+
+/// Computes the density of the normal distribution at x.
+///
+/// x: value
+/// mu: mean
+/// sigma: standard deviation
+///
+/// returns: float probability density
+pub fn eval_normal_pdf(x: f64, mu: f64, sigma: f64) -> f64 {
+    let z = (x - mu) / sigma;
+    (-z * z / 2.0).exp() / (sigma * (2.0 * PI).sqrt())
+}
+
+/// Evaluates the CDF of the normal distribution.
+///
+/// x: float
+/// mu: mean parameter
+/// sigma: standard deviation parameter
+///
+/// returns: float, accurate in the tails too
+pub fn eval_normal_cdf(x: f64, mu: f64, sigma: f64) -> f64 {
+    0.5 * erfc(-(x - mu) / (sigma * SQRT_2))
+}
+
+/// Evaluates the inverse CDF of the normal distribution.
+///
+/// Uses Peter Acklam's rational approximation,
+/// then one step of Halley's method to make it as accurate as `eval_normal_cdf`.
+///
+/// p: float 0-1, else returns NAN
+/// mu: mean parameter
+/// sigma: standard deviation parameter
+///
+/// returns: float
+#[allow(clippy::excessive_precision)]
+pub fn eval_normal_cdf_inverse(p: f64, mu: f64, sigma: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e+01,
+                         2.209460984245205e+02,
+                         -2.759285104469687e+02,
+                         1.383577518672690e+02,
+                         -3.066479806614716e+01,
+                         2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01,
+                         1.615858368580409e+02,
+                         -1.556989798598866e+02,
+                         6.680131188771972e+01,
+                         -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03,
+                         -3.223964580411365e-01,
+                         -2.400758277161838e+00,
+                         -2.549732539343734e+00,
+                         4.374664141464968e+00,
+                         2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03,
+                         3.224671290700398e-01,
+                         2.445134137142996e+00,
+                         3.754408661907416e+00];
+    const P_LOW: f64 = 0.02425;
+
+    if !(0.0..=1.0).contains(&p) {
+        return f64::NAN;
+    }
+    if p == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p == 1.0 {
+        return f64::INFINITY;
+    }
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]) /
+        ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    let mut z = if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q /
+        (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    };
+    // refine, the error cdf(z) - p is worked out in whichever tail z is in,
+    // so that it keeps its accuracy there
+    let e = if z > 0.0 {
+        (1.0 - p) - 0.5 * erfc(z / SQRT_2)
+    } else {
+        0.5 * erfc(-z / SQRT_2) - p
+    };
+    let u = e * (2.0 * PI).sqrt() * (z * z / 2.0).exp();
+    z -= u / (1.0 + z * u / 2.0);
+    mu + sigma * z
+}
+
+/// Makes a Pmf discrete approx to a Gaussian distribution.
+///
+/// mu: float mean
+/// sigma: float standard deviation
+/// num_sigmas: how many sigmas to extend in each direction
+/// n: number of values in the Pmf
+///
+/// returns: normalized Pmf
+pub fn make_gaussian_pmf(mu: f64, sigma: f64, num_sigmas: f64, n: usize) -> Pmf<Real> {
+    let low = mu - num_sigmas * sigma;
+    let high = mu + num_sigmas * sigma;
+    Gaussian::new(mu, sigma).make_pmf_range(low, high, n)
+}
+
+/// Represents the PDF of a Gaussian distribution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gaussian {
+    mu: f64,
+    sigma: f64,
+}
+
+impl Gaussian {
+    /// Constructs a Gaussian Pdf with given mu and sigma.
+    ///     mu: mean
+    ///     sigma: standard deviation
+    /// panics if sigma is not positive.
+    pub fn new(mu: f64, sigma: f64) -> Gaussian {
+        assert!(sigma > 0.0, "Gaussian: sigma must be positive");
+        Gaussian { mu, sigma }
+    }
+
+    pub fn mu(&self) -> f64 {
+        self.mu
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+}
+
+impl Pdf for Gaussian {
+    fn density(&self, x: f64) -> f64 {
+        eval_normal_pdf(x, self.mu, self.sigma)
+    }
+
+    fn log_density(&self, x: f64) -> f64 {
+        let z = (x - self.mu) / self.sigma;
+        -z * z / 2.0 - (self.sigma * (2.0 * PI).sqrt()).ln()
+    }
+
+    fn cdf(&self, x: f64) -> Option<f64> {
+        Some(eval_normal_cdf(x, self.mu, self.sigma))
+    }
+
+    fn quantile(&self, p: f64) -> Option<f64> {
+        Some(eval_normal_cdf_inverse(p, self.mu, self.sigma))
+    }
+}
+
+#[cfg(test)]
+mod tests_gaussian {
+    use super::*;
+    #[test]
+    fn gaussian_erf() {
+        // from python's math.erf and math.erfc
+        let cases = [(-3.3, -0.9999969422902035, 1.9999969422902035),
+                     (-1.0, -0.8427007929497149, 1.842700792949715),
+                     (-0.3, -0.3286267594591274, 1.3286267594591274),
+                     (0.2, 0.22270258921047847, 0.7772974107895215),
+                     (0.46875, 0.49261347321793797, 0.507386526782062),
+                     (0.5, 0.5204998778130465, 0.4795001221869535),
+                     (1.5, 0.9661051464753108, 0.033894853524689274),
+                     (3.9, 0.9999999652077514, 3.479224859723177e-08),
+                     (4.0, 0.9999999845827421, 1.541725790028002e-08),
+                     (4.5, 0.9999999998033839, 1.9661604415428873e-10),
+                     (8.0, 1.0, 1.1224297172982928e-29)];
+        for &(x, e, c) in &cases {
+            assert_relative_eq!{erf(x), e, max_relative = 1e-15};
+            assert_relative_eq!{erfc(x), c, max_relative = 1e-14};
+        }
+        assert_eq!(erf(0.0), 0.0);
+        assert_eq!(erfc(-30.0), 2.0);
+        assert!(erfc(30.0) >= 0.0);
+    }
+    #[test]
+    fn gaussian_cdf() {
+        // from python's statistics.NormalDist
+        assert_relative_eq!{eval_normal_cdf(-6.0, 0.0, 1.0), 9.865876449133282e-10, max_relative = 1e-14};
+        assert_relative_eq!{eval_normal_cdf(13.0, 10.0, 2.0), 0.9331927987311419, max_relative = 1e-15};
+        assert_eq!(eval_normal_cdf(0.0, 0.0, 1.0), 0.5);
+        let cases = [(1e-300, -37.0470962993612),
+                     (1e-10, -6.361340902404056),
+                     (0.001, -3.090232306167813),
+                     (0.02425, -1.9729610513118845),
+                     (0.1, -1.2815515655446008),
+                     (0.8, 0.8416212335729144),
+                     (0.975, 1.9599639845400536),
+                     (0.999999, 4.753424308817089)];
+        for &(p, x) in &cases {
+            assert_relative_eq!{eval_normal_cdf_inverse(p, 0.0, 1.0), x, max_relative = 1e-14};
+        }
+        assert_eq!(eval_normal_cdf_inverse(0.5, 3.0, 2.0), 3.0);
+        assert_eq!(eval_normal_cdf_inverse(0.0, 0.0, 1.0), f64::NEG_INFINITY);
+        assert!(eval_normal_cdf_inverse(1.5, 0.0, 1.0).is_nan());
+    }
+    #[test]
+    fn gaussian_pmf() {
+        let gaussian = Gaussian::new(10.0, 2.0);
+        assert_relative_eq!{gaussian.density(11.0), 0.17603266338214976, max_relative = 1e-15};
+        assert_relative_eq!{gaussian.log_density(11.0), gaussian.density(11.0).ln(), max_relative = 1e-15};
+        assert_relative_eq!{gaussian.quantile(gaussian.cdf(7.0).unwrap()).unwrap(), 7.0, max_relative = 1e-14};
+        let pmf = make_gaussian_pmf(10.0, 2.0, 4.0, 201);
+        assert_eq!(pmf.values().len(), 201);
+        assert_relative_eq!{pmf.mean(), 10.0, max_relative = 1e-12};
+        assert_eq!(pmf.maximum_likelihood(), &Real::new(10.0));
+        assert_relative_eq!{pmf.make_cdf().prob(Real::new(12.0)), gaussian.cdf(12.0).unwrap(), epsilon = 0.01};
+    }
+}
@@ -0,0 +1,308 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use std::marker::PhantomData;
+
+use super::{HasPmf, Joint, Pmf, Real, SuiteError, UpdateStats};
+use super::suite::{scale, update_set_probs};
+
+/// A suite over every combination of values of several parameters.
+///
+/// Each parameter has an axis of values, usually from `linspace`,
+/// and the probabilities are stored densely in one Vec, with the last axis varying fastest.
+/// This is much faster than a `Pmf` over tuples, and the hypotheses are plain `&[f64]`
+/// in the same order as the axes.
+/// It does not implement `Suite`, as it has no `Pmf`,
+/// but the update methods work the same way.
+pub struct GridSuite<D, F>
+    where F: Fn(&D, &[f64]) -> f64
+{
+    axes: Vec<Vec<f64>>,
+    probs: Vec<f64>,
+    likelihood: F,
+    data: PhantomData<fn(&D)>,
+}
+
+impl<D, F> GridSuite<D, F>
+    where F: Fn(&D, &[f64]) -> f64
+{
+    /// Makes a suite with a uniform prior.
+    /// Args:
+    ///     axes: the values of each parameter
+    ///     likelihood: function of (data, values of the parameters) that computes the likelihood
+    /// panics if there are no axes, or one of them is empty.
+    pub fn new(axes: Vec<Vec<f64>>, likelihood: F) -> GridSuite<D, F> {
+        assert!(!axes.is_empty() && axes.iter().all(|axis| !axis.is_empty()),
+                "GridSuite: every axis needs at least one value");
+        let n: usize = axes.iter().map(Vec::len).product();
+        GridSuite {
+            axes,
+            probs: vec![1.0 / n as f64; n],
+            likelihood,
+            data: PhantomData,
+        }
+    }
+
+    /// Replaces the prior, then normalizes.
+    ///     prior: function from the values of the parameters to a prior weight
+    pub fn set_prior<P: Fn(&[f64]) -> f64>(&mut self, prior: P) {
+        let probs = &mut self.probs;
+        for_each_point(&self.axes, |k, params| probs[k] = prior(params));
+        let total = self.probs.iter().fold(0.0, |s, p| s + p);
+        if total == 0.0 {
+            panic!{"Normalize: total probability is zero."};
+        }
+        scale(self.probs.iter_mut(), total);
+    }
+
+    /// Gets the values of each parameter.
+    pub fn axes(&self) -> &[Vec<f64>] {
+        &self.axes
+    }
+
+    /// Gets all the probabilities, with the last axis varying fastest.
+    pub fn probs(&self) -> &[f64] {
+        &self.probs
+    }
+
+    /// Gets the probability of one combination.
+    ///     index: the index into each axis
+    pub fn prob(&self, index: &[usize]) -> f64 {
+        assert_eq!(index.len(), self.axes.len(), "prob: need one index per axis");
+        let k = index.iter().zip(self.axes.iter()).fold(0, |k, (&i, axis)| {
+            assert!(i < axis.len(), "prob: index out of range");
+            k * axis.len() + i
+        });
+        self.probs[k]
+    }
+
+    /// Updates each hypothesis based on the data.
+    ///    data: any representation of the data
+    ///    returns: the normalizing constant
+    /// panics if every hypothesis is ruled out, leaving the suite as it was.
+    pub fn update(&mut self, data: &D) -> f64 {
+        let mut probs = self.probs.clone();
+        let total = mult_likelihoods(&self.axes, &mut probs, &self.likelihood, data);
+        if total == 0.0 {
+            panic!{"Normalize: total probability is zero."};
+        }
+        scale(probs.iter_mut(), total);
+        self.probs = probs;
+        total
+    }
+
+    /// Updates each hypothesis based on the dataset, like `Suite::update_set`.
+    ///     dataset: a sequence of data
+    ///     returns: UpdateStats with the log of the normalizing constant,
+    ///     or SuiteError::ImpossibleData if every hypothesis is ruled out,
+    ///     in which case the suite is left as it was before the update.
+    pub fn update_set<'a, I: Iterator<Item = &'a D>>(&mut self,
+                                                     dataset: I)
+                                                     -> Result<UpdateStats, SuiteError>
+        where D: 'a
+    {
+        let mut probs = self.probs.clone();
+        let (axes, likelihood) = (&self.axes, &self.likelihood);
+        let stats = update_set_probs(&mut probs, dataset, |data, probs| {
+            mult_likelihoods(axes, probs, likelihood, data)
+        })?;
+        self.probs = probs;
+        Ok(stats)
+    }
+
+    /// Computes the marginal likelihood of the dataset, like `Suite::evidence`,
+    ///     without modifying the suite.
+    ///     This underflows to zero for big datasets, use `log_evidence` for those.
+    ///     dataset: a sequence of data
+    ///     returns: the total probability of the dataset
+    pub fn evidence<'a, I: Iterator<Item = &'a D>>(&self, dataset: I) -> f64
+        where D: 'a
+    {
+        self.log_evidence(dataset).exp()
+    }
+
+    /// Computes the log of the marginal likelihood of the dataset, like `Suite::log_evidence`,
+    ///     renormalizing a copy of the probabilities as it goes.
+    ///     dataset: a sequence of data
+    ///     returns: the log of the total probability of the dataset,
+    ///     -inf if every hypothesis is ruled out or the suite has no probability
+    pub fn log_evidence<'a, I: Iterator<Item = &'a D>>(&self, dataset: I) -> f64
+        where D: 'a
+    {
+        let mut probs = self.probs.clone();
+        let prior = probs.iter().fold(0.0, |s, p| s + p);
+        let (axes, likelihood) = (&self.axes, &self.likelihood);
+        let stats = update_set_probs(&mut probs, dataset, |data, probs| {
+            mult_likelihoods(axes, probs, likelihood, data)
+        });
+        match stats {
+            Ok(stats) => stats.log_evidence - prior.ln(),
+            Err(SuiteError::ImpossibleData) => f64::NEG_INFINITY,
+        }
+    }
+
+    /// Gets the marginal distribution of one parameter.
+    ///     axis: index of the parameter
+    ///     Returns: Pmf
+    pub fn marginal(&self, axis: usize) -> Pmf<Real> {
+        let mut pmf = Pmf::new();
+        for (&x, prob) in self.axes[axis].iter().zip(self.marginal_probs(axis)) {
+            pmf.incr(Real::new(x), prob);
+        }
+        pmf
+    }
+
+    /// Gets the joint distribution of two parameters.
+    ///     a, b: indexes of the parameters
+    ///     Returns: Joint over (value of a, value of b)
+    /// panics if a and b are the same.
+    pub fn joint(&self, a: usize, b: usize) -> Joint<(Real, Real)> {
+        assert!(a != b, "joint: needs two different axes");
+        let (stride_a, stride_b) = (self.stride(a), self.stride(b));
+        let (len_a, len_b) = (self.axes[a].len(), self.axes[b].len());
+        let mut joint = Joint::new();
+        for (k, &prob) in self.probs.iter().enumerate() {
+            let x = self.axes[a][k / stride_a % len_a];
+            let y = self.axes[b][k / stride_b % len_b];
+            joint.get_mut_pmf().incr((Real::new(x), Real::new(y)), prob);
+        }
+        joint
+    }
+
+    /// Computes the posterior mean of one parameter.
+    pub fn mean(&self, axis: usize) -> f64 {
+        self.axes[axis]
+            .iter()
+            .zip(self.marginal_probs(axis))
+            .fold(0.0, |s, (x, p)| s + x * p)
+    }
+
+    /// Computes the posterior mean of every parameter.
+    pub fn means(&self) -> Vec<f64> {
+        (0..self.axes.len()).map(|axis| self.mean(axis)).collect()
+    }
+
+    /// Returns the combination of values with the highest probability.
+    pub fn maximum_likelihood(&self) -> Vec<f64> {
+        let best = self.probs
+            .iter()
+            .enumerate()
+            .fold(0, |best, (k, &prob)| if prob > self.probs[best] { k } else { best });
+        (0..self.axes.len())
+            .map(|axis| self.axes[axis][best / self.stride(axis) % self.axes[axis].len()])
+            .collect()
+    }
+
+    /// How far apart in `probs` neighbors along the axis are.
+    fn stride(&self, axis: usize) -> usize {
+        self.axes[axis + 1..].iter().map(Vec::len).product()
+    }
+
+    fn marginal_probs(&self, axis: usize) -> Vec<f64> {
+        let stride = self.stride(axis);
+        let len = self.axes[axis].len();
+        let mut out = vec![0.0; len];
+        for (k, &prob) in self.probs.iter().enumerate() {
+            out[k / stride % len] += prob;
+        }
+        out
+    }
+}
+
+/// Calls f with the flat index and the values of the parameters of every combination,
+/// in the order they are stored.
+fn for_each_point<G: FnMut(usize, &[f64])>(axes: &[Vec<f64>], mut f: G) {
+    let n: usize = axes.iter().map(Vec::len).product();
+    let mut index = vec![0; axes.len()];
+    let mut params: Vec<f64> = axes.iter().map(|axis| axis[0]).collect();
+    for k in 0..n {
+        f(k, &params);
+        for a in (0..axes.len()).rev() {
+            index[a] += 1;
+            if index[a] < axes[a].len() {
+                params[a] = axes[a][index[a]];
+                break;
+            }
+            index[a] = 0;
+            params[a] = axes[a][0];
+        }
+    }
+}
+
+/// Multiplies each hypothesis by the likelihood of the data, in place.
+/// Hypotheses that are already ruled out are skipped.
+/// Returns the new total.
+fn mult_likelihoods<D, F>(axes: &[Vec<f64>], probs: &mut [f64], likelihood: &F, data: &D) -> f64
+    where F: Fn(&D, &[f64]) -> f64
+{
+    let mut total = 0.0;
+    for_each_point(axes, |k, params| {
+        if probs[k] != 0.0 {
+            probs[k] *= likelihood(data, params);
+            total += probs[k];
+        }
+    });
+    total
+}
+
+#[cfg(test)]
+mod tests_grid {
+    use super::*;
+    use super::super::linspace;
+    #[test]
+    fn grid_three_axes() {
+        // the likelihood only depends on the first two parameters
+        let mut suite = GridSuite::new(vec![linspace(0.0, 1.0, 3), vec![1.0, 2.0], linspace(-1.0, 1.0, 5)],
+                                       |&data: &f64, params: &[f64]| params[0] * params[1] * data);
+        assert_eq!(suite.probs().len(), 30);
+        assert_ulps_eq!{suite.update(&1.0), 0.75, max_ulps = 4}
+        assert_eq!(suite.prob(&[0, 1, 3]), 0.0);
+        assert_ulps_eq!{suite.prob(&[2, 1, 3]), 2.0 / 4.5 / 5.0, max_ulps = 4}
+        assert_ulps_eq!{suite.marginal(1).prob(&Real::new(2.0), 0.0), 2.0 / 3.0, max_ulps = 4}
+        assert_ulps_eq!{suite.marginal(2).prob(&Real::new(0.5), 0.0), 0.2, max_ulps = 4}
+        assert_ulps_eq!{suite.mean(2), 0.0, epsilon = 1e-15}
+        assert_ulps_eq!{suite.mean(0), 5.0 / 6.0, max_ulps = 4}
+        let joint = suite.joint(1, 0);
+        assert_ulps_eq!{joint.get_pmf().prob(&(Real::new(2.0), Real::new(1.0)), 0.0),
+                        4.0 / 9.0,
+                        max_ulps = 4}
+        assert_eq!(suite.maximum_likelihood()[..2], [1.0, 2.0]);
+        let stats = suite.update_set([1.0, 1.0].iter()).unwrap();
+        assert_eq!(stats.eliminated, 0);
+        let before = suite.probs().to_vec();
+        assert_eq!(suite.update_set([1.0, 0.0].iter()), Err(SuiteError::ImpossibleData));
+        assert_eq!(suite.probs(), &before[..]);
+    }
+    #[test]
+    fn grid_evidence() {
+        let mut suite = GridSuite::new(vec![linspace(0.0, 1.0, 3)],
+                                       |&heads: &bool, params: &[f64]| if heads { params[0] } else { 1.0 - params[0] });
+        let dataset = [true, true, false];
+        // (0 + 0.125 + 0) / 3
+        assert_ulps_eq!{suite.evidence(dataset.iter()), 0.125 / 3.0, max_ulps = 4}
+        let stats = suite.update_set(dataset.iter()).unwrap();
+        assert_ulps_eq!{stats.evidence(), 0.125 / 3.0, max_ulps = 4}
+        assert_eq!(stats.eliminated, 2);
+    }
+    #[test]
+    fn grid_log_evidence() {
+        let mut suite = GridSuite::new(vec![linspace(0.0, 1.0, 101)],
+                                       |&heads: &bool, params: &[f64]| if heads { params[0] } else { 1.0 - params[0] });
+        // 0.5^2000 underflows, so the raw evidence is 0
+        let flips: Vec<bool> = (0..2000).map(|i| i % 2 == 0).collect();
+        assert_eq!(suite.evidence(flips.iter()), 0.0);
+        let log_evidence = suite.log_evidence(flips.iter());
+        assert!(log_evidence.is_finite());
+        let stats = suite.update_set(flips.iter()).unwrap();
+        assert_relative_eq!{log_evidence, stats.log_evidence, max_relative = 1e-12};
+        // heads is impossible if the coin never lands heads
+        let never = GridSuite::new(vec![vec![0.0]],
+                                   |&heads: &bool, params: &[f64]| if heads { params[0] } else { 1.0 - params[0] });
+        assert_eq!(never.log_evidence([true].iter()), f64::NEG_INFINITY);
+        assert_eq!(never.evidence([true].iter()), 0.0);
+    }
+}
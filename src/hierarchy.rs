@@ -0,0 +1,221 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use std::cmp::Eq;
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::hash::{BuildHasherDefault, Hash};
+use std::iter;
+use std::marker::PhantomData;
+use fnv::FnvHasher;
+
+use super::{make_mixture, HasPmf, Pmf, Suite, SuiteError, UpdateStats};
+use super::suite::set_log_posteriors;
+
+/// A suite whose hypotheses each own a lower level suite.
+///
+/// The likelihood of the data under a top level hypothesis
+/// is the marginal likelihood (evidence) of its lower level suite,
+/// and updating the top level updates the lower levels too.
+/// As it is a `Suite` itself, it can be the lower level of another, for deeper models.
+///
+/// Every update, tempered and weighted ones included, updates the lower levels too,
+/// so `marginal` always mixes lower level posteriors of the same data.
+/// Discounting panics, as moving the top level back toward a prior
+/// would leave it out of step with the lower levels.
+/// Lower level suites whose hypothesis has been ruled out are not updated any more.
+pub struct Hierarchical<K, V, S>
+    where K: Eq + Hash + Clone,
+          V: Eq + Hash + Clone,
+          S: HasPmf<V>
+{
+    pmf: Pmf<K>,
+    children: HashMap<K, S, BuildHasherDefault<FnvHasher>>,
+    hypos: PhantomData<fn() -> V>,
+}
+
+impl<K, V, S> Hierarchical<K, V, S>
+    where K: Eq + Hash + Clone,
+          V: Eq + Hash + Clone,
+          S: HasPmf<V>
+{
+    /// Makes a hierarchy with a uniform prior over the top level.
+    /// Args:
+    ///     children: (top level hypothesis, its lower level suite) pairs
+    pub fn new<I: IntoIterator<Item = (K, S)>>(children: I) -> Hierarchical<K, V, S> {
+        let mut out = Hierarchical {
+            pmf: Pmf::new(),
+            children: HashMap::default(),
+            hypos: PhantomData,
+        };
+        for (hypo, child) in children {
+            out.pmf.set(hypo.clone(), 1.0);
+            out.children.insert(hypo, child);
+        }
+        out.pmf.normalize(1.0);
+        out
+    }
+
+    /// Gets the lower level suite of a top level hypothesis.
+    pub fn child(&self, hypo: &K) -> Option<&S> {
+        self.children.get(hypo)
+    }
+
+    /// Iterates over the (top level hypothesis, lower level suite) pairs.
+    pub fn children(&self) -> hash_map::Iter<'_, K, S> {
+        self.children.iter()
+    }
+
+    /// Gets the marginal posterior of the lower level,
+    ///     the lower level Pmfs mixed by the probability of their top level hypothesis.
+    ///     Returns: Pmf
+    pub fn marginal(&self) -> Pmf<V> {
+        self.marginal_by(|child| child.get_pmf().clone())
+    }
+
+    /// Mixes any distribution made from the lower level suites
+    ///     by the probability of their top level hypothesis.
+    ///     For a deeper hierarchy, `marginal_by(|child| child.marginal())`
+    ///     gets the marginal posterior two levels down.
+    ///     f: function from a lower level suite to a Pmf
+    ///     Returns: Pmf
+    pub fn marginal_by<O, F>(&self, f: F) -> Pmf<O>
+        where O: Eq + Hash + Clone,
+              F: Fn(&S) -> Pmf<O>
+    {
+        let dists: Vec<(Pmf<O>, f64)> = self.pmf
+            .iter()
+            .map(|(hypo, &prob)| (f(&self.children[hypo]), prob))
+            .collect();
+        make_mixture(dists.iter().map(|(dist, prob)| (dist, *prob)))
+    }
+
+    /// Multiplies each top level hypothesis by the evidence its lower level suite found,
+    /// in log space.
+    /// A lower level suite that finds the data impossible is left as it was,
+    /// so if they all do, the whole hierarchy is left as it was.
+    /// If any is updated its hypothesis stays possible, and the update goes through.
+    fn update_children<F>(&mut self, update_child: F) -> Result<UpdateStats, SuiteError>
+        where F: Fn(&mut S) -> Result<UpdateStats, SuiteError>
+    {
+        let children = &mut self.children;
+        let log_posts: Vec<f64> = self.pmf
+            .iter()
+            .map(|(hypo, &prob)| if prob == 0.0 {
+                f64::NEG_INFINITY
+            } else {
+                match update_child(children.get_mut(hypo).unwrap()) {
+                    Ok(stats) => prob.ln() + stats.log_evidence,
+                    Err(SuiteError::ImpossibleData) => f64::NEG_INFINITY,
+                }
+            })
+            .collect();
+        set_log_posteriors(&mut self.pmf, &log_posts)
+    }
+}
+
+impl<K, V, S> HasPmf<K> for Hierarchical<K, V, S>
+    where K: Eq + Hash + Clone,
+          V: Eq + Hash + Clone,
+          S: HasPmf<V>
+{
+    fn get_pmf(&self) -> &Pmf<K> {
+        &self.pmf
+    }
+    fn get_mut_pmf(&mut self) -> &mut Pmf<K> {
+        &mut self.pmf
+    }
+
+    /// panics, the lower levels can not be discounted toward a top level Pmf.
+    fn discount(&mut self, _rate: f64, _toward: &Pmf<K>) {
+        panic!{"discount: a Hierarchical suite can not be discounted"};
+    }
+
+    /// panics, discounting only the top level would leave it out of step with the lower levels.
+    fn discount_flat(&mut self, _rate: f64) {
+        panic!{"discount: a Hierarchical suite can not be discounted"};
+    }
+}
+
+impl<D, K, V, S> Suite<D, K> for Hierarchical<K, V, S>
+    where K: Eq + Hash + Clone,
+          V: Eq + Hash + Clone,
+          S: Suite<D, V>
+{
+    /// The marginal likelihood of the data under the lower level suite.
+    fn likelihood(&self, data: &D, hypo: &K) -> f64 {
+        self.children[hypo].evidence(iter::once(data))
+    }
+
+    fn update(&mut self, data: &D) -> f64 {
+        match self.update_set(iter::once(data)) {
+            Ok(stats) => stats.evidence(),
+            Err(SuiteError::ImpossibleData) => panic!{"Normalize: total probability is zero."},
+        }
+    }
+
+    fn update_set<'a, I: Iterator<Item = &'a D>>(&mut self,
+                                                 dataset: I)
+                                                 -> Result<UpdateStats, SuiteError>
+        where D: 'a
+    {
+        let dataset: Vec<&D> = dataset.collect();
+        self.update_children(|child| child.update_set(dataset.iter().cloned()))
+    }
+
+    fn update_counts<'a, I: Iterator<Item = (&'a D, u32)>>(&mut self,
+                                                           counts: I)
+                                                           -> Result<UpdateStats, SuiteError>
+        where D: 'a
+    {
+        let counts: Vec<(&D, u32)> = counts.collect();
+        self.update_children(|child| child.update_counts(counts.iter().cloned()))
+    }
+
+    /// Tempers the update of each lower level suite,
+    /// the same as `update_set_weighted` with one datum.
+    fn update_tempered(&mut self, data: &D, power: f64) -> f64 {
+        assert!(power >= 0.0, "update_tempered: power must be 0 or more");
+        match self.update_set_weighted(iter::once((data, power))) {
+            Ok(stats) => stats.evidence(),
+            Err(SuiteError::ImpossibleData) => panic!{"Normalize: total probability is zero."},
+        }
+    }
+
+    fn update_set_weighted<'a, I: Iterator<Item = (&'a D, f64)>>(&mut self,
+                                                                 dataset: I)
+                                                                 -> Result<UpdateStats, SuiteError>
+        where D: 'a
+    {
+        let dataset: Vec<(&D, f64)> = dataset.collect();
+        // check before any lower level is updated
+        assert!(dataset.iter().all(|&(_, weight)| weight >= 0.0),
+                "update_set_weighted: weights must be 0 or more");
+        self.update_children(|child| child.update_set_weighted(dataset.iter().cloned()))
+    }
+
+    /// Mixes the log evidence of the lower level suites, shifted by the largest.
+    fn log_evidence<'a, I: Iterator<Item = &'a D>>(&self, dataset: I) -> f64
+        where D: 'a
+    {
+        let dataset: Vec<&D> = dataset.collect();
+        let logs: Vec<f64> = self.pmf
+            .iter()
+            .map(|(hypo, &prob)| if prob == 0.0 {
+                f64::NEG_INFINITY
+            } else {
+                prob.ln() + self.children[hypo].log_evidence(dataset.iter().cloned())
+            })
+            .collect();
+        let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if max == f64::NEG_INFINITY {
+            return max;
+        }
+        let total = logs.iter().fold(0.0, |s, log| s + (log - max).exp());
+        max + total.ln() - self.pmf.total().ln()
+    }
+}
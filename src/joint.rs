@@ -0,0 +1,219 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use std::cmp::Eq;
+use std::hash::Hash;
+
+use super::{HasPmf, Pmf};
+
+/// One position of a tuple, so that a `Joint` can be marginalized over it.
+///
+/// `Axis<0>` is the first element, `Axis<1>` the second, and so on.
+/// It is implemented for tuples of up to 4 hashable elements.
+pub trait Axis<const N: usize> {
+    type Value: Eq + Hash + Clone;
+    fn axis(&self) -> &Self::Value;
+}
+
+macro_rules! impl_axis {
+    ($($n:tt => $t:ident),+ ; $all:tt) => { $(impl_axis!(@one $n $t $all);)+ };
+    (@one $n:tt $t:ident ($($a:ident),+)) => {
+        impl<$($a: Eq + Hash + Clone),+> Axis<$n> for ($($a,)+) {
+            type Value = $t;
+            fn axis(&self) -> &$t {
+                &self.$n
+            }
+        }
+    };
+}
+
+impl_axis!(0 => A, 1 => B; (A, B));
+impl_axis!(0 => A, 1 => B, 2 => C; (A, B, C));
+impl_axis!(0 => A, 1 => B, 2 => C, 3 => E; (A, B, C, E));
+
+/// Represents a joint distribution.
+///
+/// The values are sequences (usually tuples)
+/// and the probabilities are stored in a `Pmf`.
+/// It implements `HasPmf`, so a suite over several parameters can be composed around one.
+#[derive(Clone, PartialEq)]
+pub struct Joint<V: Eq + Hash + Clone> {
+    pmf: Pmf<V>,
+}
+
+impl<V: Eq + Hash + Clone> Joint<V> {
+    pub fn new() -> Joint<V> {
+        Joint { pmf: Pmf::new() }
+    }
+
+    /// Consumes the joint, returning the Pmf over the tuples.
+    pub fn into_pmf(self) -> Pmf<V> {
+        self.pmf
+    }
+
+    /// Gets the marginal distribution of the indicated variable.
+    ///     I: index of the variable we want
+    ///     Returns: Pmf
+    pub fn marginal<const I: usize>(&self) -> Pmf<<V as Axis<I>>::Value>
+        where V: Axis<I>
+    {
+        self.marginal_by(|v| v.axis().clone())
+    }
+
+    /// Gets the marginal distribution of any function of the values,
+    ///     for axes of sequences that are not tuples, or combinations of them.
+    ///     f: function from a value to the variable we want
+    ///     Returns: Pmf
+    pub fn marginal_by<K, F>(&self, f: F) -> Pmf<K>
+        where K: Eq + Hash + Clone,
+              F: Fn(&V) -> K
+    {
+        let mut pmf = Pmf::new();
+        for (v, &prob) in self.pmf.iter() {
+            pmf.incr(f(v), prob);
+        }
+        pmf
+    }
+
+    /// Gets the conditional distribution of the indicated variable.
+    ///     Distribution of vs[I], conditioned on vs[J] = val.
+    ///     I: index of the variable we want
+    ///     J: which variable is conditioned on
+    ///     val: the value the jth variable has to have
+    ///     Returns: Pmf
+    /// panics if val has no probability.
+    pub fn conditional<const I: usize, const J: usize>(&self,
+                                                       val: &<V as Axis<J>>::Value)
+                                                       -> Pmf<<V as Axis<I>>::Value>
+        where V: Axis<I> + Axis<J>
+    {
+        self.conditional_by(|v| Axis::<I>::axis(v).clone(),
+                            |v| Axis::<J>::axis(v) == val)
+    }
+
+    /// Gets the conditional distribution of any function of the values,
+    ///     given that a condition holds.
+    ///     f: function from a value to the variable we want
+    ///     given: which values to condition on
+    ///     Returns: Pmf
+    /// panics if no value that meets the condition has any probability.
+    pub fn conditional_by<K, F, G>(&self, f: F, given: G) -> Pmf<K>
+        where K: Eq + Hash + Clone,
+              F: Fn(&V) -> K,
+              G: Fn(&V) -> bool
+    {
+        let mut pmf = Pmf::new();
+        for (v, &prob) in self.pmf.iter() {
+            if given(v) {
+                pmf.incr(f(v), prob);
+            }
+        }
+        pmf.normalize(1.0);
+        pmf
+    }
+}
+
+impl<V: Eq + Hash + Clone + Ord> Joint<V> {
+    /// Returns the maximum-likelihood credible region.
+    ///     The smallest set of values that has the given probability,
+    ///     the most likely first, e.g. for drawing contours.
+    ///     percentage: float 0-100
+    ///     returns: list of values from the joint
+    pub fn max_like_interval(&self, percentage: f64) -> Vec<&V> {
+        self.pmf.max_like_set(percentage)
+    }
+}
+
+impl<A: Eq + Hash + Clone, B: Eq + Hash + Clone> Joint<(A, B)> {
+    /// Joint distribution of values from pmf1 and pmf2,
+    /// assuming they are independent.
+    ///     Args:
+    ///         pmf1, pmf2: Pmf objects
+    ///     Returns: Joint
+    pub fn from_independent(pmf1: &Pmf<A>, pmf2: &Pmf<B>) -> Joint<(A, B)> {
+        let mut pmf = Pmf::new();
+        for (v1, &p1) in pmf1.iter() {
+            for (v2, &p2) in pmf2.iter() {
+                pmf.set((v1.clone(), v2.clone()), p1 * p2);
+            }
+        }
+        Joint { pmf }
+    }
+
+    /// Splits the joint into the marginals of its two variables.
+    /// Only the product of these is the same as the joint if the variables are independent.
+    pub fn to_independent(&self) -> (Pmf<A>, Pmf<B>) {
+        (self.marginal::<0>(), self.marginal::<1>())
+    }
+}
+
+impl<V: Eq + Hash + Clone> From<Pmf<V>> for Joint<V> {
+    fn from(pmf: Pmf<V>) -> Joint<V> {
+        Joint { pmf }
+    }
+}
+
+impl<V: Eq + Hash + Clone> HasPmf<V> for Joint<V> {
+    fn get_pmf(&self) -> &Pmf<V> {
+        &self.pmf
+    }
+    fn get_mut_pmf(&mut self) -> &mut Pmf<V> {
+        &mut self.pmf
+    }
+}
+
+impl<V: Eq + Hash + Clone> Default for Joint<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests_joint {
+    use super::*;
+    #[test]
+    fn joint_marginal_conditional() {
+        let mut joint = Joint::new();
+        joint.get_mut_pmf().set((1, 'a'), 0.1);
+        joint.get_mut_pmf().set((1, 'b'), 0.3);
+        joint.get_mut_pmf().set((2, 'a'), 0.2);
+        joint.get_mut_pmf().set((2, 'b'), 0.4);
+        let first = joint.marginal::<0>();
+        assert_ulps_eq!{first.prob(&1, 0.0), 0.4, max_ulps = 4}
+        assert_ulps_eq!{joint.marginal::<1>().prob(&'b', 0.0), 0.7, max_ulps = 4}
+        let given = joint.conditional::<0, 1>(&'a');
+        assert_ulps_eq!{given.prob(&2, 0.0), 2.0 / 3.0, max_ulps = 4}
+        assert_ulps_eq!{joint.marginal_by(|&(n, _)| n % 2).prob(&0, 0.0), 0.6, max_ulps = 4}
+    }
+    #[test]
+    fn joint_independent() {
+        let mut a = Pmf::new();
+        a.set(1, 0.25);
+        a.set(2, 0.75);
+        let mut b = Pmf::new();
+        b.set("x", 0.5);
+        b.set("y", 0.5);
+        let joint = Joint::from_independent(&a, &b);
+        assert_ulps_eq!{joint.get_pmf().prob(&(2, "y"), 0.0), 0.375, max_ulps = 4}
+        let (a2, b2) = joint.to_independent();
+        assert_ulps_eq!{a2.prob(&1, 0.0), 0.25, max_ulps = 4}
+        assert_ulps_eq!{b2.prob(&"x", 0.0), 0.5, max_ulps = 4}
+        assert_ulps_eq!{joint.conditional::<1, 0>(&1).prob(&"x", 0.0), 0.5, max_ulps = 4}
+    }
+    #[test]
+    fn joint_triple() {
+        let mut a = Pmf::new();
+        a.set(1, 0.5);
+        a.set(2, 0.5);
+        let mut joint = Joint::new();
+        for ((x, y), p) in Joint::from_independent(&a, &a).into_pmf().items() {
+            joint.get_mut_pmf().set((x, y, x + y), p);
+        }
+        assert_ulps_eq!{joint.marginal::<2>().prob(&3, 0.0), 0.5, max_ulps = 4}
+        assert_ulps_eq!{joint.conditional::<0, 2>(&4).prob(&2, 0.0), 1.0, max_ulps = 4}
+    }
+}
@@ -0,0 +1,190 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use std::f64::consts::PI;
+
+use super::{eval_normal_cdf, Pdf};
+
+/// The shape of the bump put on each point of the sample.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kernel {
+    /// The standard normal density, the bandwidth is its standard deviation.
+    Gaussian,
+    /// 3/4 (1 - u^2) for |u| <= 1, the bandwidth is its half width.
+    Epanechnikov,
+    /// A flat top hat for |u| <= 1, the bandwidth is its half width.
+    Uniform,
+}
+
+impl Kernel {
+    fn density(self, u: f64) -> f64 {
+        match self {
+            Kernel::Gaussian => (-u * u / 2.0).exp() / (2.0 * PI).sqrt(),
+            Kernel::Epanechnikov => if u.abs() <= 1.0 { 0.75 * (1.0 - u * u) } else { 0.0 },
+            Kernel::Uniform => if u.abs() <= 1.0 { 0.5 } else { 0.0 },
+        }
+    }
+
+    fn cdf(self, u: f64) -> f64 {
+        match self {
+            Kernel::Gaussian => eval_normal_cdf(u, 0.0, 1.0),
+            Kernel::Epanechnikov => {
+                let u = u.clamp(-1.0, 1.0);
+                0.5 + 0.75 * u - 0.25 * u * u * u
+            }
+            Kernel::Uniform => (u.clamp(-1.0, 1.0) + 1.0) / 2.0,
+        }
+    }
+}
+
+/// How wide to make the kernel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Bandwidth {
+    /// Scott's rule, the standard deviation of the sample times n^(-1/5),
+    /// the default of scipy.stats.gaussian_kde.
+    Scott,
+    /// Silverman's rule, the standard deviation of the sample times (3n/4)^(-1/5).
+    Silverman,
+    /// Exactly this bandwidth.
+    Fixed(f64),
+}
+
+/// Represents a PDF estimated by KDE (kernel density estimation).
+///
+/// The density is the weighted average of a kernel centered on each point of the sample.
+/// For weighted samples n is the effective sample size, (sum w)^2 / sum w^2,
+/// and the standard deviation is weighted too, as in scipy.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EstimatedPdf {
+    sample: Vec<f64>,
+    weights: Vec<f64>,
+    kernel: Kernel,
+    bandwidth: f64,
+}
+
+impl EstimatedPdf {
+    /// Estimates the density from a sample, all the points weighted the same.
+    ///     sample: sequence of data
+    ///     kernel: the shape of the bump on each point
+    ///     bandwidth: how wide to make the bumps
+    /// panics if the sample is empty, or the rule gives a bandwidth of 0.
+    pub fn new(sample: &[f64], kernel: Kernel, bandwidth: Bandwidth) -> EstimatedPdf {
+        EstimatedPdf::weighted(sample, &vec![1.0; sample.len()], kernel, bandwidth)
+    }
+
+    /// Estimates the density from a sample with a weight for each point.
+    ///     sample: sequence of data
+    ///     weights: a weight for each point, only the ratios matter
+    ///     kernel: the shape of the bump on each point
+    ///     bandwidth: how wide to make the bumps
+    /// panics if the sample is empty, the weights do not match the sample
+    /// or are negative, or the bandwidth is not positive.
+    pub fn weighted(sample: &[f64],
+                    weights: &[f64],
+                    kernel: Kernel,
+                    bandwidth: Bandwidth)
+                    -> EstimatedPdf {
+        assert!(!sample.is_empty(), "EstimatedPdf: the sample is empty");
+        assert_eq!(sample.len(), weights.len(), "EstimatedPdf: need one weight per point");
+        assert!(weights.iter().all(|&w| w >= 0.0), "EstimatedPdf: weights can not be negative");
+        let total = weights.iter().fold(0.0, |s, w| s + w);
+        assert!(total > 0.0, "EstimatedPdf: the weights add up to 0");
+        let weights: Vec<f64> = weights.iter().map(|w| w / total).collect();
+
+        let bandwidth = match bandwidth {
+            Bandwidth::Fixed(h) => h,
+            rule => {
+                let sum_sq = weights.iter().fold(0.0, |s, w| s + w * w);
+                let n_eff = 1.0 / sum_sq;
+                let mean = sample.iter().zip(&weights).fold(0.0, |s, (x, w)| s + x * w);
+                let var = sample.iter()
+                    .zip(&weights)
+                    .fold(0.0, |s, (x, w)| s + w * (x - mean) * (x - mean)) /
+                          (1.0 - sum_sq);
+                let factor = match rule {
+                    Bandwidth::Silverman => (n_eff * 3.0 / 4.0).powf(-0.2),
+                    _ => n_eff.powf(-0.2),
+                };
+                var.sqrt() * factor
+            }
+        };
+        assert!(bandwidth > 0.0,
+                "EstimatedPdf: the bandwidth must be positive, use Fixed for a sample with no spread");
+        EstimatedPdf {
+            sample: sample.to_vec(),
+            weights,
+            kernel,
+            bandwidth,
+        }
+    }
+
+    /// Gets the bandwidth, worked out by the rule if one was given.
+    pub fn bandwidth(&self) -> f64 {
+        self.bandwidth
+    }
+}
+
+impl Pdf for EstimatedPdf {
+    fn density(&self, x: f64) -> f64 {
+        let h = self.bandwidth;
+        self.sample
+            .iter()
+            .zip(&self.weights)
+            .fold(0.0, |s, (xi, w)| s + w * self.kernel.density((x - xi) / h)) / h
+    }
+
+    fn cdf(&self, x: f64) -> Option<f64> {
+        let h = self.bandwidth;
+        Some(self.sample
+            .iter()
+            .zip(&self.weights)
+            .fold(0.0, |s, (xi, w)| s + w * self.kernel.cdf((x - xi) / h)))
+    }
+}
+
+#[cfg(test)]
+mod tests_kde {
+    use super::*;
+    const PRICES: [f64; 12] = [26000.0, 27500.0, 29000.0, 31200.0, 32500.0, 33100.0, 35000.0,
+                               36200.0, 38800.0, 41000.0, 44500.0, 52000.0];
+    #[test]
+    fn kde_scott() {
+        // the same as scipy.stats.gaussian_kde
+        let pdf = EstimatedPdf::new(&PRICES, Kernel::Gaussian, Bandwidth::Scott);
+        assert_relative_eq!{pdf.bandwidth(), 4585.082837504615, max_relative = 1e-12};
+        assert_relative_eq!{pdf.density(30000.0), 4.582386271874682e-05, max_relative = 1e-12};
+        assert_relative_eq!{pdf.density(50000.0), 1.1674764136519097e-05, max_relative = 1e-12};
+        assert_relative_eq!{pdf.cdf(1e6).unwrap(), 1.0, max_relative = 1e-12};
+        // the cdf is the integral of the density
+        let area = (0..3500).fold(0.0, |s, i| s + pdf.density(10.0 * f64::from(i) + 5.0) * 10.0);
+        assert_relative_eq!{pdf.cdf(35000.0).unwrap() - pdf.cdf(0.0).unwrap(), area, max_relative = 1e-6};
+    }
+    #[test]
+    fn kde_weighted_silverman() {
+        let weights = [1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, 3.0, 3.0, 3.0, 3.0];
+        let pdf = EstimatedPdf::weighted(&PRICES, &weights, Kernel::Gaussian, Bandwidth::Silverman);
+        assert_relative_eq!{pdf.bandwidth(), 5062.742633598196, max_relative = 1e-12};
+        assert_relative_eq!{pdf.density(30000.0), 3.338130001367824e-05, max_relative = 1e-12};
+        assert_relative_eq!{pdf.density(50000.0), 1.7738614606012275e-05, max_relative = 1e-12};
+    }
+    #[test]
+    fn kde_kernels() {
+        let pdf = EstimatedPdf::new(&PRICES, Kernel::Epanechnikov, Bandwidth::Fixed(3000.0));
+        assert_relative_eq!{pdf.density(30000.0), 4.875e-05, max_relative = 1e-12};
+        assert_eq!(pdf.density(60000.0), 0.0);
+        assert_eq!(pdf.cdf(20000.0), Some(0.0));
+        assert_eq!(pdf.cdf(60000.0), Some(1.0));
+        let pdf = EstimatedPdf::new(&[0.0, 10.0], Kernel::Uniform, Bandwidth::Fixed(1.0));
+        assert_eq!(pdf.density(0.5), 0.25);
+        assert_eq!(pdf.cdf(5.0), Some(0.5));
+    }
+    #[test]
+    #[should_panic]
+    fn kde_no_spread() {
+        EstimatedPdf::new(&[3.0, 3.0], Kernel::Gaussian, Bandwidth::Scott);
+    }
+}
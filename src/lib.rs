@@ -11,6 +11,11 @@ extern crate fnv;
 extern crate rand;
 extern crate itertools;
 
+#[cfg(feature = "derive")]
+extern crate think_bayes_derive;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+
 #[cfg(test)]
 #[macro_use]
 extern crate approx;
@@ -25,9 +30,56 @@ pub use pmf::*;
 
 pub mod suite;
 pub use suite::*;
+// the derive lives in the macro namespace so it does not clash with the trait.
+#[cfg(feature = "derive")]
+pub use think_bayes_derive::HasPmf;
 
 pub mod cdf;
 pub use cdf::*;
 
 pub mod simulation;
 pub use simulation::*;
+
+pub mod evidence;
+pub use evidence::*;
+
+pub mod trace;
+pub use trace::*;
+
+pub mod joint;
+pub use joint::*;
+
+pub mod real;
+pub use real::*;
+
+pub mod grid;
+pub use grid::*;
+
+pub mod dirichlet;
+pub use dirichlet::*;
+
+pub mod hierarchy;
+pub use hierarchy::*;
+
+pub mod pdf;
+pub use pdf::*;
+
+pub mod gaussian;
+pub use gaussian::*;
+
+pub mod kde;
+pub use kde::*;
+
+pub mod special;
+pub use special::*;
+
+pub mod beta;
+pub use beta::*;
+
+pub mod poisson;
+pub use poisson::*;
+
+#[cfg(feature = "parallel")]
+pub mod parallel;
+#[cfg(feature = "parallel")]
+pub use parallel::*;
@@ -0,0 +1,69 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+// Only built with the "parallel" feature.
+use std::cmp::Eq;
+use std::hash::Hash;
+use rayon::prelude::*;
+
+use super::{Suite, SuiteError, UpdateStats};
+use super::suite::{apply_likelihoods, set_log_posteriors};
+
+/// Multi-threaded versions of the `Suite` updates.
+///
+/// Likelihoods are evaluated for all the hypotheses in parallel,
+/// then combined in a fixed order, so the result does not depend on the thread count.
+/// This only pays off for big suites or expensive likelihoods.
+/// It is implemented for every `Suite` that is `Sync`.
+pub trait ParSuite<D, V>: Suite<D, V> + Sync
+    where D: Sync,
+          V: Eq + Hash + Clone + Send + Sync
+{
+    /// Updates each hypothesis based on the data, like `Suite::update`.
+    ///    data: any representation of the data
+    ///    returns: the normalizing constant
+    /// panics if every hypothesis is ruled out, leaving the suite as it was.
+    fn par_update(&mut self, data: &D) -> f64 {
+        let likes: Vec<f64> = {
+            let suite = &*self;
+            let hypos: Vec<&V> = suite.get_pmf().iter().map(|(hypo, _)| hypo).collect();
+            hypos.par_iter().map(|hypo| suite.likelihood(data, hypo)).collect()
+        };
+        apply_likelihoods(self.get_mut_pmf(), &likes)
+    }
+
+    /// Updates each hypothesis based on the dataset, like `Suite::update_set`.
+    ///     Each thread multiplies up the likelihoods of whole hypotheses in log space,
+    ///     so it never needs to renormalize part way through.
+    ///     dataset: a sequence of data
+    ///     returns: UpdateStats, or SuiteError::ImpossibleData if every hypothesis is ruled out,
+    ///     in which case the suite is left as it was
+    fn par_update_set<'a, I: Iterator<Item = &'a D>>(&mut self,
+                                                     dataset: I)
+                                                     -> Result<UpdateStats, SuiteError>
+        where D: 'a
+    {
+        let dataset: Vec<&D> = dataset.collect();
+        let log_posts: Vec<f64> = {
+            let suite = &*self;
+            let items: Vec<(&V, f64)> = suite.get_pmf().iter().map(|(hypo, &prob)| (hypo, prob)).collect();
+            items.par_iter()
+                .map(|&(hypo, prob)| {
+                    dataset.iter().fold(prob.ln(), |s, data| s + suite.likelihood(data, hypo).ln())
+                })
+                .collect()
+        };
+        set_log_posteriors(self.get_mut_pmf(), &log_posts)
+    }
+}
+
+impl<D, V, S> ParSuite<D, V> for S
+    where S: Suite<D, V> + Sync,
+          D: Sync,
+          V: Eq + Hash + Clone + Send + Sync
+{
+}
@@ -0,0 +1,115 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use super::{linspace, Pmf, Real};
+
+/// Represents a probability density function (PDF).
+///
+/// Only `density` has to be written, the rest have defaults.
+/// A continuous distribution is put to work with `Pmf` and `Suite`
+/// by evaluating it on a grid with `make_pmf`.
+pub trait Pdf {
+    /// Evaluates this Pdf at x.
+    ///     Returns: float probability density
+    fn density(&self, x: f64) -> f64;
+
+    /// Evaluates the log of this Pdf at x.
+    ///     Override it when the density underflows far from the center.
+    ///     Returns: float log probability density
+    fn log_density(&self, x: f64) -> f64 {
+        self.density(x).ln()
+    }
+
+    /// Evaluates the cumulative distribution function at x, if it is known.
+    ///     Returns: float probability, or None
+    fn cdf(&self, _x: f64) -> Option<f64> {
+        None
+    }
+
+    /// Evaluates the inverse of the cdf at p, if it is known.
+    ///     p: float 0-1
+    ///     Returns: the x with cdf(x) = p, or None
+    fn quantile(&self, _p: f64) -> Option<f64> {
+        None
+    }
+
+    /// Makes a discrete version of this Pdf, evaluated at xs.
+    ///     The densities are computed in log space and scaled by the largest,
+    ///     so a narrow Pdf does not underflow to all zeros.
+    ///     xs: equally-spaced sequence of values
+    ///     Returns: normalized Pmf
+    /// panics if the density is zero at every x.
+    fn make_pmf(&self, xs: &[f64]) -> Pmf<Real> {
+        let logs: Vec<f64> = xs.iter().map(|&x| self.log_density(x)).collect();
+        let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if max == f64::NEG_INFINITY {
+            panic!{"make_pmf: the density is zero at every x."};
+        }
+        let mut pmf = Pmf::new();
+        for (&x, &log) in xs.iter().zip(logs.iter()) {
+            pmf.incr(Real::new(x), (log - max).exp());
+        }
+        pmf.normalize(1.0);
+        pmf
+    }
+
+    /// Makes a discrete version of this Pdf, evaluated at n equally-spaced
+    ///     points from low to high, both included.
+    ///     Returns: normalized Pmf
+    fn make_pmf_range(&self, low: f64, high: f64, n: usize) -> Pmf<Real> {
+        self.make_pmf(&linspace(low, high, n))
+    }
+}
+
+#[cfg(test)]
+mod tests_pdf {
+    use super::*;
+    struct Exponential {
+        lam: f64,
+    }
+    impl Pdf for Exponential {
+        fn density(&self, x: f64) -> f64 {
+            if x < 0.0 { 0.0 } else { self.lam * (-self.lam * x).exp() }
+        }
+        fn cdf(&self, x: f64) -> Option<f64> {
+            Some(if x < 0.0 { 0.0 } else { 1.0 - (-self.lam * x).exp() })
+        }
+    }
+    #[test]
+    fn pdf_make_pmf() {
+        let pdf = Exponential { lam: 2.0 };
+        assert_eq!(pdf.quantile(0.5), None);
+        assert_ulps_eq!{pdf.cdf(0.5).unwrap(), 1.0 - (-1.0f64).exp(), max_ulps = 4}
+        let pmf = pdf.make_pmf_range(-1.0, 10.0, 1101);
+        assert_eq!(pmf.prob(&Real::new(-0.5), 1.0), 0.0);
+        assert_eq!(pmf.maximum_likelihood(), &Real::new(0.0));
+        assert_relative_eq!{pmf.mean(), 0.5, max_relative = 1e-2};
+        let pmf = pdf.make_pmf(&[1.0, 2.0]);
+        assert_ulps_eq!{pmf.prob(&Real::new(2.0), 0.0), 1.0 / (1.0 + 2.0f64.exp()), max_ulps = 4}
+    }
+    #[test]
+    fn pdf_make_pmf_far_out() {
+        // the density underflows, but the log density does not
+        struct Narrow;
+        impl Pdf for Narrow {
+            fn density(&self, x: f64) -> f64 {
+                self.log_density(x).exp()
+            }
+            fn log_density(&self, x: f64) -> f64 {
+                -x * x * 1e4
+            }
+        }
+        let pmf = Narrow.make_pmf(&[10.0, 10.01]);
+        assert_eq!(Narrow.density(10.0), 0.0);
+        assert!(pmf.prob(&Real::new(10.0), 0.0) > 0.5);
+    }
+    #[test]
+    #[should_panic(expected = "the density is zero at every x")]
+    fn pdf_make_pmf_all_zero() {
+        Exponential { lam: 2.0 }.make_pmf(&[-2.0, -1.0]);
+    }
+}
@@ -42,6 +42,18 @@ impl<V: Eq + Hash + Clone> Pmf<V> {
         self.d.iter().map(|(val, prb)| (val.clone(), prb.clone())).collect()
     }
 
+    /// Iterates over the (value, freq/prob) pairs, in no particular order,
+    /// without copying them out like `items` does.
+    pub fn iter(&self) -> impl Iterator<Item = (&V, &f64)> + '_ {
+        self.d.iter()
+    }
+
+    /// Iterates over the (value, freq/prob) pairs, in no particular order,
+    /// allowing the freqs/probs to be changed in place.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&V, &mut f64)> + '_ {
+        self.d.iter_mut()
+    }
+
     /// Returns the total of the frequencies/probabilities in the map.
     pub fn total(&self) -> f64 {
         self.d.values().fold(0.0, |s, p| s + p)
@@ -232,6 +244,44 @@ impl<V: Eq + Hash + Clone + Ord> Pmf<V> {
         (self.percentile(p), self.percentile(100.0 - p))
     }
 
+    /// Finds the smallest set of values that has the given probability,
+    ///     by taking the most likely values first.
+    ///     For a joint distribution this is a credible region.
+    ///     Values with equal probabilities are taken smallest first,
+    ///     and the running total is allowed a little rounding error,
+    ///     so ten values of 0.1 make 90% after nine.
+    ///     percentage: float 0-100, of the total so the Pmf need not be normalized
+    ///     returns: the values, most likely first
+    pub fn max_like_set(&self, percentage: f64) -> Vec<&V> {
+        const ROUNDING: f64 = 1e-9;
+        let target = percentage / 100.0 * self.total() * (1.0 - ROUNDING);
+        let mut items: Vec<(&V, &f64)> = self.d.iter().collect();
+        items.sort_by(|a, b| b.1.total_cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        let mut total = 0.0;
+        let mut interval = Vec::new();
+        for (val, &prob) in items {
+            interval.push(val);
+            total += prob;
+            if total >= target {
+                break;
+            }
+        }
+        interval
+    }
+
+    /// Computes the highest probability interval of a given Pmf,
+    ///     the smallest and largest values in `max_like_set`.
+    ///     Unlike `credible_interval` it is not centered, for a skewed
+    ///     distribution it is shifted toward the mode.
+    ///     percentage: float 0-100
+    ///     returns: (low, high) values from the Pmf
+    pub fn max_like_interval(&self, percentage: f64) -> (&V, &V) {
+        let set = self.max_like_set(percentage);
+        let low = set.iter().min().expect("max_like_interval of empty Pmf");
+        let high = set.iter().max().expect("max_like_interval of empty Pmf");
+        (low, high)
+    }
+
     pub fn make_cdf(&self) -> super::cdf::Cdf<V> {
         self.into()
     }
@@ -261,4 +311,40 @@ mod tests_pmf {
         assert_ulps_eq!{pmf.prob(&"the", 0.0), 0.25, max_ulps = 4}
         assert_ulps_eq!{pmf.prob(&"foo", 0.0), 0.125, max_ulps = 4}
     }
+    #[test]
+    fn pmf_iter_mut() {
+        let mut pmf = Pmf::new();
+        for &x in &[1, 2, 3] {
+            pmf.set(x, 1.0)
+        }
+        for (&x, prob) in pmf.iter_mut() {
+            *prob *= f64::from(x);
+        }
+        assert_ulps_eq!{pmf.iter().map(|(_, &prob)| prob).sum::<f64>(), 6.0, max_ulps = 4}
+        assert_ulps_eq!{pmf.prob(&3, 0.0), 3.0, max_ulps = 4}
+    }
+    #[test]
+    fn pmf_max_like_set_uniform() {
+        let mut pmf = Pmf::new();
+        for x in 0..10 {
+            pmf.set(x, 1.0);
+        }
+        pmf.normalize(1.0);
+        // 0.1 added up nine times is 0.8999999999999999
+        let set: Vec<i32> = pmf.max_like_set(90.0).into_iter().cloned().collect();
+        // the ties are broken by taking the smallest values first
+        assert_eq!(set, (0..9).collect::<Vec<_>>());
+        assert_eq!(pmf.max_like_interval(90.0), (&0, &8));
+    }
+    #[test]
+    fn pmf_max_like_set_unnormalized() {
+        let mut pmf = Pmf::new();
+        for x in 0..10 {
+            pmf.set(x, 5.0);
+        }
+        assert_eq!(pmf.max_like_set(50.0).len(), 5);
+        assert_eq!(pmf.max_like_interval(50.0), (&0, &4));
+        pmf.set(7, 50.0);
+        assert_eq!(pmf.max_like_set(50.0), vec![&7]);
+    }
 }
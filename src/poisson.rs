@@ -0,0 +1,159 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+// The Poisson distribution of the number of events in an interval,
+// and the exponential distribution of the time between them.
+
+use super::{ln_factorial, Pdf, Pmf, Real};
+
+/// Computes the log of the Poisson PMF,
+/// so a large rate does not overflow lam^k or k!.
+///
+/// k: number of events
+/// lam: parameter lambda in events per unit time
+///
+/// returns: float log probability
+pub fn eval_log_poisson_pmf(k: u32, lam: f64) -> f64 {
+    if lam == 0.0 {
+        // 0^0 is 1, all the probability is on no events
+        return if k == 0 { 0.0 } else { f64::NEG_INFINITY };
+    }
+    f64::from(k) * lam.ln() - lam - ln_factorial(k)
+}
+
+/// Computes the Poisson PMF.
+///
+/// k: number of events
+/// lam: parameter lambda in events per unit time
+///
+/// returns: float probability
+pub fn eval_poisson_pmf(k: u32, lam: f64) -> f64 {
+    eval_log_poisson_pmf(k, lam).exp()
+}
+
+/// Makes a PMF discrete approx to a Poisson distribution.
+///     The probabilities are computed in log space and scaled by the largest,
+///     so the Pmf is good even if the truncation cuts off most of it.
+///
+/// lam: parameter lambda in events per unit time
+/// high: upper bound of the Pmf
+/// step: distance between the counts in the Pmf
+///
+/// returns: normalized Pmf
+pub fn make_poisson_pmf(lam: f64, high: u32, step: u32) -> Pmf<u32> {
+    assert!(step > 0, "make_poisson_pmf: step must be positive");
+    let ks: Vec<u32> = (0..high + 1).step_by(step as usize).collect();
+    let logs: Vec<f64> = ks.iter().map(|&k| eval_log_poisson_pmf(k, lam)).collect();
+    let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let mut pmf = Pmf::new();
+    for (&k, &log) in ks.iter().zip(logs.iter()) {
+        pmf.set(k, (log - max).exp());
+    }
+    pmf.normalize(1.0);
+    pmf
+}
+
+/// Computes the exponential PDF.
+///
+/// x: value
+/// lam: parameter lambda in events per unit time
+///
+/// returns: float probability density, 0 for x < 0
+pub fn eval_exponential_pdf(x: f64, lam: f64) -> f64 {
+    Exponential::new(lam).density(x)
+}
+
+/// Makes a PMF discrete approx to an exponential distribution.
+///
+/// lam: parameter lambda in events per unit time
+/// high: upper bound
+/// n: number of values in the Pmf
+///
+/// returns: normalized Pmf
+pub fn make_exponential_pmf(lam: f64, high: f64, n: usize) -> Pmf<Real> {
+    Exponential::new(lam).make_pmf_range(0.0, high, n)
+}
+
+/// Represents the PDF of an exponential distribution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exponential {
+    lam: f64,
+}
+
+impl Exponential {
+    /// Initializes an exponential distribution.
+    ///     lam: parameter lambda in events per unit time
+    /// panics if lam is not positive.
+    pub fn new(lam: f64) -> Exponential {
+        assert!(lam > 0.0, "Exponential: lam must be positive");
+        Exponential { lam }
+    }
+
+    /// Gets lambda, the rate.
+    pub fn lam(&self) -> f64 {
+        self.lam
+    }
+}
+
+impl Pdf for Exponential {
+    fn density(&self, x: f64) -> f64 {
+        if x < 0.0 { 0.0 } else { self.lam * (-self.lam * x).exp() }
+    }
+
+    fn log_density(&self, x: f64) -> f64 {
+        if x < 0.0 { f64::NEG_INFINITY } else { self.lam.ln() - self.lam * x }
+    }
+
+    fn cdf(&self, x: f64) -> Option<f64> {
+        Some(if x < 0.0 { 0.0 } else { -(-self.lam * x).exp_m1() })
+    }
+
+    fn quantile(&self, p: f64) -> Option<f64> {
+        if (0.0..=1.0).contains(&p) {
+            Some(-(-p).ln_1p() / self.lam)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_poisson {
+    use super::*;
+    #[test]
+    fn poisson_eval() {
+        assert_relative_eq!{eval_poisson_pmf(3, 2.8), 0.22248374912479743, max_relative = 1e-13};
+        assert_eq!(eval_poisson_pmf(0, 0.0), 1.0);
+        assert_eq!(eval_poisson_pmf(2, 0.0), 0.0);
+        // 1000^1000 and 1000! both overflow
+        assert_relative_eq!{eval_log_poisson_pmf(1000, 1000.0), -4.372899506026442, max_relative = 1e-12};
+    }
+    #[test]
+    fn poisson_make_pmf() {
+        let pmf = make_poisson_pmf(2.8, 30, 1);
+        assert_relative_eq!{pmf.mean(), 2.8, max_relative = 1e-12};
+        assert_eq!(pmf.maximum_likelihood(), &2);
+        let pmf = make_poisson_pmf(2.8, 10, 2);
+        assert_eq!(pmf.values().len(), 6);
+        assert!(pmf.values().iter().all(|k| k % 2 == 0));
+        assert_ulps_eq!{pmf.total(), 1.0, max_ulps = 4}
+        // truncated far below the rate, where every probability underflows
+        let far = make_poisson_pmf(10000.0, 100, 50);
+        assert_eq!(far.maximum_likelihood(), &100);
+    }
+    #[test]
+    fn poisson_exponential() {
+        assert_eq!(eval_exponential_pdf(-1.0, 2.0), 0.0);
+        assert_ulps_eq!{eval_exponential_pdf(0.5, 2.0), 2.0 / 1.0f64.exp(), max_ulps = 4}
+        let exp = Exponential::new(2.0);
+        assert_relative_eq!{exp.cdf(exp.quantile(0.9).unwrap()).unwrap(), 0.9, max_relative = 1e-14};
+        assert_eq!(exp.quantile(1.5), None);
+        let pmf = make_exponential_pmf(2.0, 10.0, 2001);
+        assert_eq!(pmf.maximum_likelihood(), &Real::new(0.0));
+        assert_relative_eq!{pmf.mean(), 0.5, max_relative = 1e-2};
+    }
+}
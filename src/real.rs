@@ -0,0 +1,90 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// A float that is not NaN, so it can be a value in a Pmf.
+///
+/// As the README says, floats make poor keys because arithmetic on them is inexact.
+/// This is for values that are made once and looked up as is,
+/// like the points of a grid from `linspace`, not for sums of floats.
+/// -0.0 is stored as 0.0, so that equal values hash the same.
+#[derive(Clone, Copy, Debug)]
+pub struct Real(f64);
+
+impl Real {
+    /// panics if x is NaN.
+    pub fn new(x: f64) -> Real {
+        assert!(!x.is_nan(), "Real: NaN is not a value");
+        Real(if x == 0.0 { 0.0 } else { x })
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+impl PartialEq for Real {
+    fn eq(&self, other: &Real) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Real {}
+
+impl Hash for Real {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state)
+    }
+}
+
+impl PartialOrd for Real {
+    fn partial_cmp(&self, other: &Real) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Real {
+    fn cmp(&self, other: &Real) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl From<Real> for f64 {
+    fn from(x: Real) -> f64 {
+        x.0
+    }
+}
+
+impl fmt::Display for Real {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests_real {
+    use super::*;
+    use super::super::Pmf;
+    #[test]
+    fn real_pmf() {
+        let mut pmf = Pmf::new();
+        pmf.set(Real::new(0.5), 0.25);
+        pmf.set(Real::new(1.5), 0.75);
+        pmf.incr(Real::new(-0.0), 0.0);
+        assert_ulps_eq!{pmf.prob(&Real::new(0.0), 1.0), 0.0, max_ulps = 4}
+        assert_ulps_eq!{pmf.mean(), 1.25, max_ulps = 4}
+        assert_eq!{pmf.percentile(50.0), &Real::new(1.5)}
+    }
+    #[test]
+    #[should_panic]
+    fn real_nan() {
+        Real::new(f64::NAN);
+    }
+}
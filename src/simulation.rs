@@ -9,7 +9,7 @@ use std::hash::Hash;
 use std::cmp::Ord;
 use std::ops::Add;
 use itertools::Itertools;
-use super::{Cdf, Pmf};
+use super::{Cdf, CdfError, Pmf};
 
 /// Chooses a random value from each dist and returns the sum.
 ///
@@ -17,7 +17,7 @@ use super::{Cdf, Pmf};
 ///
 /// returns: numerical sum
 pub fn random_sum<'a, V, I>(dists: I) -> V
-    where V: 'a + Eq + Copy + Ord + Hash + Add<Output = V>,
+    where V: 'a + Copy + PartialOrd + Add<Output = V>,
           I: Iterator<Item = &'a Cdf<V>>
 {
     dists.map(|dist| dist.random()).fold1(|s, r| s + r).unwrap()
@@ -41,3 +41,21 @@ pub fn sample_sum<V, C, I>(dists: I, n: usize) -> Pmf<V>
     }
     pdf
 }
+
+/// Draws a sample of sums from a list of distributions.
+/// Unlike `sample_sum` the values do not need to be hashable, so this works for
+/// continuous distributions like `Cdf<f64>`.
+///
+/// dists: iter of Cdf objects (or things that convert into them)
+/// n: sample size
+///
+/// returns: new Cdf of sums, or an error if a sum can not be ordered (is NaN)
+pub fn sample_sum_cdf<V, C, I>(dists: I, n: usize) -> Result<Cdf<V>, CdfError>
+    where V: Copy + PartialOrd + Add<Output = V>,
+          C: Into<Cdf<V>>,
+          I: Iterator<Item = C>
+{
+    let dists: Vec<_> = dists.map(|i| i.into()).collect();
+    let sums: Vec<V> = (0..n).map(|_| random_sum(dists.iter())).collect();
+    Cdf::from_list(&sums)
+}
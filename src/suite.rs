@@ -1,44 +1,601 @@
 use super::pmf::*;
+use super::utils::make_predictive;
 use std::cmp::Eq;
 use std::hash::Hash;
+use std::marker::PhantomData;
+use std::collections::HashMap;
+use std::hash::BuildHasherDefault;
+use fnv::FnvHasher;
+use std::error::Error;
+use std::fmt;
+
+/// `update_set` renormalizes early if the total probability leaves this range.
+pub(crate) const RENORMALIZE_BELOW: f64 = 1e-100;
+pub(crate) const RENORMALIZE_ABOVE: f64 = 1e100;
+
+/// What happened during a `Suite::update_set`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateStats {
+    /// The log of the normalizing constant,
+    /// the total probability of the dataset if the suite was normalized.
+    pub log_evidence: f64,
+    /// How many times the suite was renormalized part way through to avoid underflow.
+    pub renormalizations: usize,
+    /// How many hypotheses went from a nonzero probability to zero,
+    /// either ruled out or too unlikely compared to the rest to represent.
+    pub eliminated: usize,
+}
+
+impl UpdateStats {
+    /// The normalizing constant, this underflows to zero for big datasets,
+    /// use `log_evidence` for those.
+    pub fn evidence(&self) -> f64 {
+        self.log_evidence.exp()
+    }
+}
+
+/// The ways updating a suite can fail.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SuiteError {
+    /// The data has zero likelihood under every hypothesis.
+    ImpossibleData,
+}
+
+impl fmt::Display for SuiteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SuiteError::ImpossibleData => write!(f, "Suite: the data is impossible under every hypothesis"),
+        }
+    }
+}
+
+impl Error for SuiteError {}
+
+/// Gives access to the Pmf a suite is composed around.
+///
+/// This is split out of `Suite` so that a type that is a suite for several kinds
+/// of data only writes it once, and so that it can be derived.
+pub trait HasPmf<V: Eq + Hash + Clone> {
+    fn get_pmf(&self) -> &Pmf<V>;
+    fn get_mut_pmf(&mut self) -> &mut Pmf<V>;
+
+    /// Makes the distribution of the next observation,
+    ///     mixing the outcome distribution of each hypothesis by its probability.
+    ///     Called before any update this is the prior predictive distribution.
+    ///     outcomes: function from a hypothesis to the Pmf of the outcome under it
+    ///     returns: Pmf of outcomes
+    fn predictive<O, F>(&self, outcomes: F) -> Pmf<O>
+        where O: Eq + Hash + Clone,
+              F: Fn(&V) -> Pmf<O>
+    {
+        make_predictive(self.get_pmf(), outcomes)
+    }
+
+    /// Moves the suite part way back toward another distribution,
+    ///     so the data seen so far counts for less. Call it between batches
+    ///     of data that drifts over time, with the prior as the target.
+    ///     rate: how far to move, 0 leaves the suite alone and 1 replaces it
+    ///     toward: the distribution to move toward, only the probabilities
+    ///     of the suite's hypotheses are used
+    /// panics if rate is not between 0 and 1, the suite has no probability,
+    /// or toward gives none of the suite's hypotheses any probability.
+    fn discount(&mut self, rate: f64, toward: &Pmf<V>) {
+        assert!((0.0..=1.0).contains(&rate), "discount: rate must be between 0 and 1");
+        let pmf = self.get_mut_pmf();
+        let total = pmf.total();
+        assert!(total > 0.0, "discount: the suite has no probability");
+        let target = pmf.iter().fold(0.0, |s, (hypo, _)| s + toward.prob(hypo, 0.0));
+        if target == 0.0 {
+            panic!{"discount: toward has no probability on the hypotheses."};
+        }
+        let keep = (1.0 - rate) / total;
+        let mix = rate / target;
+        for (hypo, prob) in pmf.iter_mut() {
+            *prob = keep * *prob + mix * toward.prob(hypo, 0.0);
+        }
+    }
+
+    /// Moves the suite part way toward giving every hypothesis the same probability,
+    ///     like `discount` with a uniform target.
+    ///     rate: how far to move, 0 leaves the suite alone and 1 makes it flat
+    /// panics if rate is not between 0 and 1, or the suite has no probability.
+    fn discount_flat(&mut self, rate: f64) {
+        assert!((0.0..=1.0).contains(&rate), "discount: rate must be between 0 and 1");
+        let pmf = self.get_mut_pmf();
+        let total = pmf.total();
+        assert!(total > 0.0, "discount: the suite has no probability");
+        let keep = (1.0 - rate) / total;
+        let mix = rate / pmf.iter().count() as f64;
+        for (_, prob) in pmf.iter_mut() {
+            *prob = keep * *prob + mix;
+        }
+    }
+}
 
 /// Represents a suite of hypotheses and their probabilities.
-pub trait Suite<D, V: Eq + Hash + Copy> {
+/// Hypotheses only need to be `Clone`, so they can own heap data like `String`s.
+/// For `Copy` types the clones are just copies.
+pub trait Suite<D, V: Eq + Hash + Clone>: HasPmf<V> {
     // Computes the likelihood of the data under the hypothesis.
     //     hypo: some representation of the hypothesis
     //     data: some representation of the data
     fn likelihood(&self, data: &D, hypo: &V) -> f64;
-    fn get_mut_pmf(&mut self) -> &mut Pmf<V>;
 
     /// Updates each hypothesis based on the data.
     ///    data: any representation of the data
-    ///    returns: the normalizing constant
-    fn update(&mut self, data: &D) {
-        let values = self.get_mut_pmf().values();
-        for hypo in values {
-            let like = self.likelihood(data, &hypo);
-            self.get_mut_pmf().mult(hypo, like)
-        }
-        self.get_mut_pmf().normalize(1.0);
+    ///    returns: the normalizing constant,
+    ///    the total probability of the data if the suite was normalized
+    /// panics if every hypothesis is ruled out, leaving the suite as it was.
+    fn update(&mut self, data: &D) -> f64 {
+        let likes = likelihoods(self, data);
+        apply_likelihoods(self.get_mut_pmf(), &likes)
     }
 
     /// Updates each hypothesis based on the dataset.
     ///     This is more efficient than calling Update repeatedly because
-    ///     it waits until the end to Normalize.
+    ///     it waits until the end to Normalize. If the probabilities get so small
+    ///     (or so big) that they are in danger of underflow, it renormalizes early.
     ///     Modifies the suite directly; if you want to keep the original, make
     ///     a copy.
     ///     dataset: a sequence of data
+    ///     returns: UpdateStats with the log of the normalizing constant,
+    ///     or SuiteError::ImpossibleData if every hypothesis is ruled out,
+    ///     in which case the suite is left as it was before the update.
+    fn update_set<'a, I: Iterator<Item = &'a D>>(&mut self,
+                                                 dataset: I)
+                                                 -> Result<UpdateStats, SuiteError>
+        where D: 'a
+    {
+        let mut probs: Vec<f64> = self.get_pmf().iter().map(|(_, &prob)| prob).collect();
+        let stats = {
+            let suite = &*self;
+            update_set_probs(&mut probs, dataset, |data, probs| mult_probs(suite, data, probs))?
+        };
+        set_probs(self.get_mut_pmf(), &probs);
+        Ok(stats)
+    }
+
+    /// Updates each hypothesis based on data that was seen several times,
+    ///     multiplying by likelihood^count in one step.
+    ///     The likelihoods are multiplied up in log space, so large counts do not underflow.
+    ///     counts: a sequence of (data, number of times it was seen)
+    ///     returns: UpdateStats with the log of the normalizing constant,
+    ///     or SuiteError::ImpossibleData if every hypothesis is ruled out.
+    fn update_counts<'a, I: Iterator<Item = (&'a D, u32)>>(&mut self,
+                                                           counts: I)
+                                                           -> Result<UpdateStats, SuiteError>
+        where D: 'a
+    {
+        update_powers(self, counts.map(|(data, count)| (data, f64::from(count))))
+    }
+
+    /// Updates each hypothesis based on the dataset, like `update_set`,
+    ///     but first groups identical observations and uses `update_counts`.
+    ///     So the likelihood is only computed once per distinct observation and hypothesis.
+    ///     dataset: a sequence of data
+    ///     returns: UpdateStats with the log of the normalizing constant,
+    ///     or SuiteError::ImpossibleData if every hypothesis is ruled out.
+    fn update_grouped<'a, I: Iterator<Item = &'a D>>(&mut self,
+                                                     dataset: I)
+                                                     -> Result<UpdateStats, SuiteError>
+        where D: 'a + Eq + Hash
+    {
+        self.update_counts(group_counts(dataset).into_iter())
+    }
+
+    /// Updates each hypothesis based on the data, with the likelihood raised to a power.
+    ///     A power below 1 makes the data count for less, above 1 for more;
+    ///     a power of 2 is the same as seeing the data twice.
+    ///     data: any representation of the data
+    ///     power: the weight of the data, 0 or more
     ///     returns: the normalizing constant
-    fn update_set<'a, I: Iterator<Item = &'a D>>(&mut self, dataset: I)
+    /// panics if power is negative or NaN,
+    /// or every hypothesis is ruled out, leaving the suite as it was.
+    fn update_tempered(&mut self, data: &D, power: f64) -> f64 {
+        assert!(power >= 0.0, "update_tempered: power must be 0 or more");
+        let likes: Vec<f64> = likelihoods(self, data).into_iter().map(|like| like.powf(power)).collect();
+        apply_likelihoods(self.get_mut_pmf(), &likes)
+    }
+
+    /// Updates each hypothesis based on a dataset where each datum has a weight,
+    ///     like `update_tempered` for each one, so older data can count for less.
+    ///     The likelihoods are multiplied up in log space, like `update_counts`.
+    ///     dataset: a sequence of (data, weight), the weights 0 or more
+    ///     returns: UpdateStats with the log of the normalizing constant,
+    ///     or SuiteError::ImpossibleData if every hypothesis is ruled out.
+    /// panics if a weight is negative or NaN, before changing anything.
+    fn update_set_weighted<'a, I: Iterator<Item = (&'a D, f64)>>(&mut self,
+                                                                 dataset: I)
+                                                                 -> Result<UpdateStats, SuiteError>
+        where D: 'a
+    {
+        update_powers(self,
+                      dataset.map(|(data, weight)| {
+                          assert!(weight >= 0.0, "update_set_weighted: weights must be 0 or more");
+                          (data, weight)
+                      }))
+    }
+
+    /// Computes the marginal likelihood of the dataset under this suite,
+    ///     the normalizing constant `update_set` finds,
+    ///     without modifying the suite.
+    ///     This underflows to zero for big datasets, use `log_evidence` for those.
+    ///     dataset: a sequence of data
+    ///     returns: the total probability of the dataset
+    fn evidence<'a, I: Iterator<Item = &'a D>>(&self, dataset: I) -> f64
+        where D: 'a
+    {
+        self.log_evidence(dataset).exp()
+    }
+
+    /// Computes the log of the marginal likelihood of the dataset under this suite,
+    ///     the `log_evidence` that `update_set` reports, without modifying the suite.
+    ///     It renormalizes a copy of the probabilities as it goes, like `update_set`,
+    ///     so a big dataset does not underflow.
+    ///     dataset: a sequence of data
+    ///     returns: the log of the total probability of the dataset,
+    ///     -inf if every hypothesis is ruled out or the suite has no probability
+    fn log_evidence<'a, I: Iterator<Item = &'a D>>(&self, dataset: I) -> f64
         where D: 'a
     {
-        let values = self.get_mut_pmf().values();
-        for data in dataset {
-            for &hypo in &values {
-                let like = self.likelihood(data, &hypo);
-                self.get_mut_pmf().mult(hypo, like)
+        let mut probs: Vec<f64> = self.get_pmf().iter().map(|(_, &prob)| prob).collect();
+        let prior = probs.iter().fold(0.0, |s, p| s + p);
+        match update_set_probs(&mut probs, dataset, |data, probs| mult_probs(self, data, probs)) {
+            Ok(stats) => stats.log_evidence - prior.ln(),
+            Err(SuiteError::ImpossibleData) => f64::NEG_INFINITY,
+        }
+    }
+}
+
+/// A suite made from a prior and a likelihood function.
+///
+/// For one-off models this saves writing a new type with a constructor and accessors.
+/// It implements `Suite`, so all the update methods work on it.
+pub struct FnSuite<D, V, F>
+    where V: Eq + Hash + Clone,
+          F: Fn(&D, &V) -> f64
+{
+    pmf: Pmf<V>,
+    likelihood: F,
+    data: PhantomData<fn(&D)>,
+}
+
+impl<D, V, F> FnSuite<D, V, F>
+    where V: Eq + Hash + Clone,
+          F: Fn(&D, &V) -> f64
+{
+    /// Args:
+    ///     prior: Pmf of the hypotheses, it is used as is so normalize it first
+    ///     likelihood: function of (data, hypo) that computes the likelihood
+    pub fn new(prior: Pmf<V>, likelihood: F) -> FnSuite<D, V, F> {
+        FnSuite {
+            pmf: prior,
+            likelihood,
+            data: PhantomData,
+        }
+    }
+
+    /// Consumes the suite, returning the posterior.
+    pub fn into_pmf(self) -> Pmf<V> {
+        self.pmf
+    }
+}
+
+impl<D, V, F> HasPmf<V> for FnSuite<D, V, F>
+    where V: Eq + Hash + Clone,
+          F: Fn(&D, &V) -> f64
+{
+    fn get_pmf(&self) -> &Pmf<V> {
+        &self.pmf
+    }
+    fn get_mut_pmf(&mut self) -> &mut Pmf<V> {
+        &mut self.pmf
+    }
+}
+
+impl<D, V, F> Suite<D, V> for FnSuite<D, V, F>
+    where V: Eq + Hash + Clone,
+          F: Fn(&D, &V) -> f64
+{
+    fn likelihood(&self, data: &D, hypo: &V) -> f64 {
+        (self.likelihood)(data, hypo)
+    }
+}
+
+/// Counts how many times each datum appears, in order of first appearance.
+fn group_counts<'a, D, I>(dataset: I) -> Vec<(&'a D, u32)>
+    where D: 'a + Eq + Hash,
+          I: Iterator<Item = &'a D>
+{
+    let mut index: HashMap<&D, usize, BuildHasherDefault<FnvHasher>> = HashMap::default();
+    let mut counts = Vec::new();
+    for data in dataset {
+        let i = *index.entry(data).or_insert(counts.len());
+        if i == counts.len() {
+            counts.push((data, 0));
+        }
+        counts[i].1 += 1;
+    }
+    counts
+}
+
+/// Computes the likelihood of the data under each hypothesis,
+/// in the order that `pmf.iter()` visits them.
+/// The suite is only borrowed, so `likelihood` sees the Pmf as it was before the update.
+fn likelihoods<D, V, S>(suite: &S, data: &D) -> Vec<f64>
+    where V: Eq + Hash + Clone,
+          S: Suite<D, V> + ?Sized
+{
+    suite.get_pmf().iter().map(|(hypo, _)| suite.likelihood(data, hypo)).collect()
+}
+
+/// Multiplies a copy of the probabilities by the likelihood of one datum,
+/// for `update_set_probs`.
+/// probs: in the order that `pmf.iter()` visits the hypotheses
+/// Returns the new total.
+fn mult_probs<D, V, S>(suite: &S, data: &D, probs: &mut [f64]) -> f64
+    where V: Eq + Hash + Clone,
+          S: Suite<D, V> + ?Sized
+{
+    let mut total = 0.0;
+    for (prob, (hypo, _)) in probs.iter_mut().zip(suite.get_pmf().iter()) {
+        *prob *= suite.likelihood(data, hypo);
+        total += *prob;
+    }
+    total
+}
+
+/// Multiplies each hypothesis by its likelihood, in place.
+/// likes: in the order that `pmf.iter()` visits the hypotheses
+/// Returns the new total.
+fn mult_likelihoods<V: Eq + Hash + Clone>(pmf: &mut Pmf<V>, likes: &[f64]) -> f64 {
+    let mut total = 0.0;
+    for ((_, prob), like) in pmf.iter_mut().zip(likes) {
+        *prob *= like;
+        total += *prob;
+    }
+    total
+}
+
+/// Multiplies each hypothesis by its likelihood and normalizes,
+/// the shared part of `update`, `update_tempered` and `ParSuite::par_update`.
+/// likes: in the order that `pmf.iter()` visits the hypotheses
+/// Returns the normalizing constant,
+/// panics if it is zero, before changing anything.
+pub(crate) fn apply_likelihoods<V: Eq + Hash + Clone>(pmf: &mut Pmf<V>, likes: &[f64]) -> f64 {
+    let total = pmf.iter().zip(likes).fold(0.0, |s, ((_, prob), like)| s + prob * like);
+    if total == 0.0 {
+        panic!{"Normalize: total probability is zero."};
+    }
+    mult_likelihoods(pmf, likes);
+    scale(pmf.iter_mut().map(|(_, prob)| prob), total);
+    total
+}
+
+/// Replaces the probabilities with probs.
+/// probs: in the order that `pmf.iter()` visits the hypotheses
+fn set_probs<V: Eq + Hash + Clone>(pmf: &mut Pmf<V>, probs: &[f64]) {
+    for ((_, prob), &new) in pmf.iter_mut().zip(probs) {
+        *prob = new;
+    }
+}
+
+/// Divides every probability by total, which was already added up and is not zero.
+pub(crate) fn scale<'a, I: IntoIterator<Item = &'a mut f64>>(probs: I, total: f64) {
+    let factor = 1.0 / total;
+    for prob in probs {
+        *prob *= factor;
+    }
+}
+
+/// The implementation of `Suite::update_set`, shared with `GridSuite`.
+/// Multiplies in the likelihood of each datum, renormalizing early if the
+/// total gets in danger of underflow, and normalizes at the end.
+/// probs: the probabilities of the hypotheses, in a fixed order;
+///     on error they are left part way through, so pass a copy
+/// mult: multiplies probs by the likelihood of one datum in place, returning the new total
+pub(crate) fn update_set_probs<'a, D, I, M>(probs: &mut [f64],
+                                            dataset: I,
+                                            mut mult: M)
+                                            -> Result<UpdateStats, SuiteError>
+    where D: 'a,
+          I: Iterator<Item = &'a D>,
+          M: FnMut(&D, &mut [f64]) -> f64
+{
+    let possible_before = probs.iter().filter(|&&prob| prob > 0.0).count();
+    let mut stats = UpdateStats {
+        log_evidence: 0.0,
+        renormalizations: 0,
+        eliminated: 0,
+    };
+    let mut total = probs.iter().fold(0.0, |s, p| s + p);
+    for data in dataset {
+        total = mult(data, probs);
+        if total == 0.0 {
+            return Err(SuiteError::ImpossibleData);
+        }
+        if !(RENORMALIZE_BELOW..=RENORMALIZE_ABOVE).contains(&total) {
+            scale(probs.iter_mut(), total);
+            stats.log_evidence += total.ln();
+            stats.renormalizations += 1;
+            total = 1.0;
+        }
+    }
+    if total == 0.0 {
+        return Err(SuiteError::ImpossibleData);
+    }
+    scale(probs.iter_mut(), total);
+    stats.log_evidence += total.ln();
+    let possible_after = probs.iter().filter(|&&prob| prob > 0.0).count();
+    stats.eliminated = possible_before - possible_after;
+    Ok(stats)
+}
+
+/// The implementation of `Suite::update_counts`.
+/// Multiplies each hypothesis by likelihood^power for each (data, power),
+/// adding up the logs so that nothing underflows until the very end.
+fn update_powers<'a, D, V, S, I>(suite: &mut S, dataset: I) -> Result<UpdateStats, SuiteError>
+    where D: 'a,
+          V: Eq + Hash + Clone,
+          S: Suite<D, V> + ?Sized,
+          I: Iterator<Item = (&'a D, f64)>
+{
+    let log_posts: Vec<f64> = {
+        let pmf = suite.get_pmf();
+        let mut log_posts: Vec<f64> = pmf.iter().map(|(_, prob)| prob.ln()).collect();
+        for (data, power) in dataset {
+            if power == 0.0 {
+                continue;
+            }
+            for (log_post, (hypo, _)) in log_posts.iter_mut().zip(pmf.iter()) {
+                if *log_post != f64::NEG_INFINITY {
+                    *log_post += power * suite.likelihood(data, hypo).ln();
+                }
+            }
+        }
+        log_posts
+    };
+    set_log_posteriors(suite.get_mut_pmf(), &log_posts)
+}
+
+/// Replaces the probabilities with the exponentials of log_posts, then normalizes.
+/// They are shifted by the largest first, so the biggest is 1 and only the
+/// ones far smaller than it underflow.
+/// Used by the log space updates, `ParSuite::par_update_set` and `Hierarchical`.
+/// log_posts: in the order that `pmf.iter()` visits the hypotheses
+/// Returns SuiteError::ImpossibleData, before changing anything, if they are all -inf.
+pub(crate) fn set_log_posteriors<V: Eq + Hash + Clone>(pmf: &mut Pmf<V>,
+                                            log_posts: &[f64])
+                                            -> Result<UpdateStats, SuiteError> {
+    let max = log_posts.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    if max == f64::NEG_INFINITY {
+        return Err(SuiteError::ImpossibleData);
+    }
+    let mut eliminated = 0;
+    let mut total = 0.0;
+    for ((_, prob), &log_post) in pmf.iter_mut().zip(log_posts.iter()) {
+        let post = (log_post - max).exp();
+        if *prob > 0.0 && post == 0.0 {
+            eliminated += 1;
+        }
+        *prob = post;
+        total += post;
+    }
+    scale(pmf.iter_mut().map(|(_, prob)| prob), total);
+    Ok(UpdateStats {
+        log_evidence: max + total.ln(),
+        renormalizations: 0,
+        eliminated,
+    })
+}
+
+#[cfg(test)]
+mod tests_suite {
+    use super::*;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    /// A suite whose likelihood depends on the current probability of the hypothesis,
+    /// and panics on negative data.
+    struct Peeking {
+        pmf: Pmf<u8>,
+    }
+    impl HasPmf<u8> for Peeking {
+        fn get_pmf(&self) -> &Pmf<u8> {
+            &self.pmf
+        }
+        fn get_mut_pmf(&mut self) -> &mut Pmf<u8> {
+            &mut self.pmf
+        }
+    }
+    impl Suite<i32, u8> for Peeking {
+        fn likelihood(&self, &data: &i32, hypo: &u8) -> f64 {
+            if data < 0 {
+                panic!("Peeking: negative data");
             }
+            assert_eq!(self.pmf.values().len(), 3);
+            self.pmf.prob(hypo, 0.0) * f64::from(data)
+        }
+    }
+    fn peeking() -> Peeking {
+        let mut pmf = Pmf::new();
+        pmf.set(0, 0.25);
+        pmf.set(1, 0.25);
+        pmf.set(2, 0.5);
+        Peeking { pmf }
+    }
+
+    #[test]
+    fn suite_likelihood_sees_pmf() {
+        let mut suite = peeking();
+        assert_ulps_eq!{suite.update(&1), 0.375, max_ulps = 4}
+        assert_ulps_eq!{suite.get_pmf().prob(&2, 0.0), 2.0 / 3.0, max_ulps = 4}
+        let mut suite = peeking();
+        suite.update_set([1, 2].iter()).unwrap();
+        suite.update_tempered(&1, 2.0);
+        suite.update_counts([(&1, 2)].iter().cloned()).unwrap();
+        assert!(suite.get_pmf().prob(&2, 0.0) > 0.9);
+    }
+    #[test]
+    fn suite_impossible_data() {
+        // data of 0 rules out every hypothesis
+        let mut suite = peeking();
+        let before = suite.get_pmf().clone();
+        assert_eq!(suite.update_set([1, 2, 0, 1].iter()), Err(SuiteError::ImpossibleData));
+        assert!(suite.get_pmf() == &before);
+        assert!(catch_unwind(AssertUnwindSafe(|| suite.update(&0))).is_err());
+        assert!(suite.get_pmf() == &before);
+        // and the suite can still be used
+        assert!(suite.update_set([2].iter()).is_ok());
+    }
+    #[test]
+    fn suite_likelihood_panics() {
+        let mut suite = peeking();
+        assert!(catch_unwind(AssertUnwindSafe(|| suite.update(&-1))).is_err());
+        assert_eq!(suite.get_pmf().values().len(), 3);
+        assert!(catch_unwind(AssertUnwindSafe(|| suite.update_set([1, -1].iter()))).is_err());
+        assert_eq!(suite.get_pmf().values().len(), 3);
+        assert!(catch_unwind(AssertUnwindSafe(|| suite.update_counts([(&-1, 2)].iter().cloned())))
+            .is_err());
+        assert_eq!(suite.get_pmf().values().len(), 3);
+    }
+    #[test]
+    fn suite_bad_weights() {
+        let panics = |f: &dyn Fn(&mut Peeking)| {
+            let mut suite = peeking();
+            let before = suite.get_pmf().clone();
+            assert!(catch_unwind(AssertUnwindSafe(|| f(&mut suite))).is_err());
+            assert!(suite.get_pmf() == &before);
+        };
+        panics(&|suite| {
+            suite.update_tempered(&1, -1.0);
+        });
+        panics(&|suite| {
+            suite.update_tempered(&1, f64::NAN);
+        });
+        panics(&|suite| {
+            suite.update_set_weighted([(&1, 1.0), (&2, -0.5)].iter().cloned()).unwrap();
+        });
+        panics(&|suite| {
+            suite.update_set_weighted([(&1, f64::NAN)].iter().cloned()).unwrap();
+        });
+        panics(&|suite| suite.discount_flat(-0.1));
+        panics(&|suite| suite.discount_flat(f64::NAN));
+        panics(&|suite| {
+            let toward = suite.get_pmf().clone();
+            suite.discount(1.5, &toward)
+        });
+    }
+    #[test]
+    fn suite_discount_no_probability() {
+        let mut suite = peeking();
+        let toward = suite.get_pmf().clone();
+        for (_, prob) in suite.get_mut_pmf().iter_mut() {
+            *prob = 0.0;
         }
-        self.get_mut_pmf().normalize(1.0);
+        assert!(catch_unwind(AssertUnwindSafe(|| suite.discount_flat(0.5))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| suite.discount(0.5, &toward))).is_err());
+        let mut empty = Peeking { pmf: Pmf::new() };
+        assert!(catch_unwind(AssertUnwindSafe(|| empty.discount_flat(0.5))).is_err());
+        assert!(catch_unwind(AssertUnwindSafe(|| empty.discount(0.5, &toward))).is_err());
     }
 }
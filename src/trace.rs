@@ -0,0 +1,156 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+use std::cmp::Eq;
+use std::hash::Hash;
+use std::io::{self, Write};
+
+use super::{HasPmf, Pmf, Suite, SuiteError, UpdateStats};
+
+/// The state of the posterior after some number of updates.
+#[derive(Clone, PartialEq)]
+pub struct TracePoint<V: Eq + Hash + Clone> {
+    /// How many updates had been done, 0 is the prior.
+    /// An `update_set` of a whole dataset counts as one.
+    pub step: usize,
+    /// The normalizing constant of the update that got here,
+    /// for a dataset it can underflow to zero.
+    pub evidence: f64,
+    /// The summary statistics, one for each column.
+    pub summary: Vec<f64>,
+    /// The whole posterior, only kept every `stride` steps.
+    pub pmf: Option<Pmf<V>>,
+}
+
+/// Wraps a suite, recording how the posterior evolves as it is updated.
+///
+/// After every update it computes some summary statistics of the posterior,
+/// and every `stride` updates it keeps a copy of the whole Pmf.
+/// The trace can be written out as CSV for plotting.
+pub struct Recorder<S, V, F>
+    where S: HasPmf<V>,
+          V: Eq + Hash + Clone,
+          F: Fn(&Pmf<V>) -> Vec<f64>
+{
+    suite: S,
+    columns: Vec<String>,
+    summarize: F,
+    stride: usize,
+    trace: Vec<TracePoint<V>>,
+}
+
+impl<S, V, F> Recorder<S, V, F>
+    where S: HasPmf<V>,
+          V: Eq + Hash + Clone,
+          F: Fn(&Pmf<V>) -> Vec<f64>
+{
+    /// Starts recording, the prior is step 0.
+    /// Args:
+    ///     suite: the suite to update
+    ///     stride: keep the whole Pmf every stride steps, 0 for never
+    ///     columns: names of the summary statistics
+    ///     summarize: computes the summary statistics from a Pmf
+    pub fn new(suite: S, stride: usize, columns: &[&str], summarize: F) -> Recorder<S, V, F> {
+        let mut out = Recorder {
+            suite,
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            summarize,
+            stride,
+            trace: Vec::new(),
+        };
+        out.record(1.0);
+        out
+    }
+
+    fn record(&mut self, evidence: f64) {
+        let step = self.trace.len();
+        let pmf = self.suite.get_pmf();
+        // None for a stride of 0, which keeps no Pmfs
+        let keep = step.checked_rem(self.stride) == Some(0);
+        self.trace.push(TracePoint {
+            step,
+            evidence,
+            summary: (self.summarize)(pmf),
+            pmf: if keep { Some(pmf.clone()) } else { None },
+        });
+    }
+
+    /// Updates the suite with one datum and records the result.
+    ///    returns: the normalizing constant
+    pub fn update<D>(&mut self, data: &D) -> f64
+        where S: Suite<D, V>
+    {
+        let evidence = self.suite.update(data);
+        self.record(evidence);
+        evidence
+    }
+
+    /// Updates the suite with each datum in turn, recording after each one.
+    ///     returns: the product of the normalizing constants
+    pub fn update_each<'a, D, I>(&mut self, dataset: I) -> f64
+        where S: Suite<D, V>,
+              D: 'a,
+              I: Iterator<Item = &'a D>
+    {
+        dataset.fold(1.0, |s, data| s * self.update(data))
+    }
+
+    /// Updates the suite with a whole dataset and records the result as one step.
+    ///     Call it once per batch to trace how the posterior evolves over datasets.
+    ///     returns: the UpdateStats of the update, or SuiteError::ImpossibleData,
+    ///     in which case the suite is unchanged and nothing is recorded.
+    pub fn update_set<'a, D, I>(&mut self, dataset: I) -> Result<UpdateStats, SuiteError>
+        where S: Suite<D, V>,
+              D: 'a,
+              I: Iterator<Item = &'a D>
+    {
+        let stats = self.suite.update_set(dataset)?;
+        self.record(stats.evidence());
+        Ok(stats)
+    }
+
+    /// Gets the wrapped suite.
+    pub fn suite(&self) -> &S {
+        &self.suite
+    }
+
+    /// Stops recording, returning the suite.
+    pub fn into_suite(self) -> S {
+        self.suite
+    }
+
+    /// Gets everything recorded so far, starting with the prior.
+    pub fn trace(&self) -> &[TracePoint<V>] {
+        &self.trace
+    }
+
+    /// Gets the Pmfs that were kept, with the step they were kept at.
+    pub fn snapshots(&self) -> Vec<(usize, &Pmf<V>)> {
+        self.trace
+            .iter()
+            .filter_map(|point| point.pmf.as_ref().map(|pmf| (point.step, pmf)))
+            .collect()
+    }
+
+    /// Writes the summary statistics as CSV, with a header line
+    /// and one line per step: step, evidence, then the columns.
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "step,evidence")?;
+        for column in &self.columns {
+            write!(out, ",{}", column)?;
+        }
+        writeln!(out)?;
+        for point in &self.trace {
+            write!(out, "{},{}", point.step, point.evidence)?;
+            for stat in &point.summary {
+                write!(out, ",{}", stat)?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
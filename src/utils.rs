@@ -129,3 +129,59 @@ pub fn make_mixture<'a, V, I>(metapmf: I) -> Pmf<V>
     }
     return mix;
 }
+
+/// Makes the predictive distribution of an outcome, a mixture over the hypotheses.
+///
+/// Given a posterior this is the posterior predictive distribution;
+/// given a prior it is the prior predictive, handy for checking that a prior is sensible.
+///
+/// Args:
+///   pmf: Pmf of the hypotheses.
+///   outcomes: function from a hypothesis to the Pmf of the outcome under it.
+///
+/// Returns: Pmf of outcomes, normalized if pmf and the outcome Pmfs are.
+pub fn make_predictive<V, O, F>(pmf: &Pmf<V>, outcomes: F) -> Pmf<O>
+    where V: Eq + Hash + Clone,
+          O: Eq + Hash + Clone,
+          F: Fn(&V) -> Pmf<O>
+{
+    let dists: Vec<(Pmf<O>, f64)> = pmf.items()
+        .into_iter()
+        .map(|(hypo, prob)| (outcomes(&hypo), prob))
+        .collect();
+    make_mixture(dists.iter().map(|&(ref dist, prob)| (dist, prob)))
+}
+
+/// Makes n evenly spaced values from low to high, both included,
+/// like numpy.linspace.
+///
+/// Args:
+///   low, high: the first and last values.
+///   n: how many values, 1 gives just low.
+///
+/// Returns: Vec of floats.
+pub fn linspace(low: f64, high: f64, n: usize) -> Vec<f64> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![low],
+        _ => {
+            let step = (high - low) / (n - 1) as f64;
+            (0..n).map(|i| if i == n - 1 { high } else { low + step * i as f64 }).collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_linspace {
+    use super::*;
+    #[test]
+    fn linspace_ends() {
+        let xs = linspace(0.0, 1.0, 11);
+        assert_eq!(xs.len(), 11);
+        assert_eq!(xs[0], 0.0);
+        assert_eq!(xs[10], 1.0);
+        assert_ulps_eq!{xs[3], 0.3, max_ulps = 4}
+        assert_eq!(linspace(2.0, 3.0, 1), vec![2.0]);
+        assert!(linspace(2.0, 3.0, 0).is_empty());
+    }
+}
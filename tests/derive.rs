@@ -0,0 +1,72 @@
+// This file contains code for use with "Think Bayes",
+// by Allen B. Downey, available from greenteapress.com
+//
+// Copyright 2012 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+// run with `cargo test --features derive`
+#![cfg(feature = "derive")]
+
+extern crate think_bayes;
+use think_bayes::pmf::*;
+use think_bayes::suite::*;
+use think_bayes::HasPmf;
+#[macro_use]
+extern crate approx;
+
+// The Monty Hall problem from 2.5 again,
+// but this time the accessors are derived, only `likelihood` is left to write.
+#[derive(HasPmf)]
+struct Monty {
+    #[pmf]
+    pmf: Pmf<char>,
+}
+
+impl Suite<char, char> for Monty {
+    fn likelihood(&self, data: &char, hypo: &char) -> f64 {
+        if data == hypo {
+            0.0
+        } else if *hypo == 'A' {
+            0.5
+        } else {
+            1.0
+        }
+    }
+}
+
+// generics and tuple structs work too
+#[derive(HasPmf)]
+struct Dice<V: Eq + ::std::hash::Hash + Copy + Into<f64> + PartialOrd>(#[pmf] Pmf<V>);
+
+impl<V: Eq + ::std::hash::Hash + Copy + Into<f64> + PartialOrd> Suite<V, V> for Dice<V> {
+    fn likelihood(&self, data: &V, hypo: &V) -> f64 {
+        if hypo < data { 0.0 } else { 1.0 / (*hypo).into() }
+    }
+}
+
+#[test]
+fn suite_derive_monty() {
+    let mut suite = Monty { pmf: Pmf::new() };
+    for v in "ABC".chars() {
+        suite.get_mut_pmf().set(v, 1.0);
+    }
+    suite.get_mut_pmf().normalize(1.0);
+    suite.update(&'B');
+    assert_ulps_eq!{suite.get_pmf().prob(&'A', 0.0), 0.3333333333333333, max_ulps = 4}
+    assert_ulps_eq!{suite.get_pmf().prob(&'B', 0.0), 0.0, max_ulps = 4}
+    assert_ulps_eq!{suite.get_pmf().prob(&'C', 0.0), 0.6666666666666666, max_ulps = 4}
+}
+
+#[test]
+fn suite_derive_dice() {
+    let mut suite = Dice(Pmf::new());
+    for &sides in &[4u32, 6, 8, 12, 20] {
+        suite.get_mut_pmf().set(sides, 1.0);
+    }
+    suite.get_mut_pmf().normalize(1.0);
+    suite.update(&6);
+    assert_ulps_eq!{suite.get_pmf().prob(&6, 0.0), 0.3921568627450981, max_ulps = 4}
+    assert_ulps_eq!{suite.0.prob(&20, 0.0), 0.11764705882352944, max_ulps = 4}
+}
@@ -33,10 +33,16 @@ impl Dice {
     }
 }
 
-impl Suite<u32, u32> for Dice {
+impl HasPmf<u32> for Dice {
+    fn get_pmf(&self) -> &Pmf<u32> {
+        &self.pmf
+    }
     fn get_mut_pmf(&mut self) -> &mut Pmf<u32> {
         &mut self.pmf
     }
+}
+
+impl Suite<u32, u32> for Dice {
     /// Computes the likelihood of the data under the hypothesis.
     /// hypo: integer number of sides on the die
     /// data: integer die roll
@@ -70,3 +76,74 @@ fn suite_dice() {
     assert_ulps_eq!{suite.get_pdf().prob(&20, 0.0), 0.001545418266496554, max_ulps = 4}
 
 }
+
+#[test]
+fn suite_dice_evidence() {
+    let rolls = [6, 6, 8, 7, 7, 5, 4];
+    let mut suite = Dice::new([4u32, 6, 8, 12, 20].iter().cloned());
+    // (1/6 + 1/8 + 1/12 + 1/20) / 5
+    assert_ulps_eq!{suite.evidence([6].iter()), 0.085, max_ulps = 4}
+    let evidence = suite.evidence(rolls.iter());
+    assert_ulps_eq!{suite.get_pdf().prob(&4, 0.0), 0.2, max_ulps = 4}
+
+    // the chain rule: the evidence is the product of the sequential normalizing constants
+    let mut product = 1.0;
+    for roll in rolls.iter() {
+        product *= suite.update(roll);
+    }
+    assert_relative_eq!{evidence, product, max_relative = 1e-12}
+
+    let mut suite = Dice::new([4u32, 6, 8, 12, 20].iter().cloned());
+    let stats = suite.update_set(rolls.iter()).unwrap();
+    assert_relative_eq!{stats.evidence(), evidence, max_relative = 1e-12}
+    assert_eq!{stats.eliminated, 2}
+    assert_eq!{stats.renormalizations, 0}
+}
+
+#[test]
+fn suite_dice_fn_suite() {
+    let mut prior = Pmf::new();
+    for &sides in &[4u32, 6, 8, 12, 20] {
+        prior.set(sides, 1.0);
+    }
+    prior.normalize(1.0);
+    let mut suite = FnSuite::new(prior, |data: &u32, hypo: &u32| {
+        if hypo < data { 0.0 } else { 1.0 / (*hypo as f64) }
+    });
+    assert_ulps_eq!{suite.update(&6), 0.085, max_ulps = 4}
+    suite.update_set([6, 8, 7, 7, 5, 4].iter()).unwrap();
+    assert_ulps_eq!{suite.get_pmf().prob(&8, 0.0), 0.9432484536722124, max_ulps = 4}
+    assert_ulps_eq!{suite.get_pmf().prob(&12, 0.0), 0.0552061280612909, max_ulps = 4}
+    let posterior = suite.into_pmf();
+    assert_ulps_eq!{posterior.prob(&20, 0.0), 0.001545418266496554, max_ulps = 4}
+}
+
+#[test]
+fn suite_dice_predictive() {
+    fn die(sides: &u32) -> Pmf<u32> {
+        let mut pmf = Pmf::new();
+        for roll in 1..(sides + 1) {
+            pmf.set(roll, 1.0 / (*sides as f64));
+        }
+        pmf
+    }
+    let mut suite = Dice::new([4u32, 6, 8, 12, 20].iter().cloned());
+    // before seeing data, the next roll is a mixture of all the dice
+    let prior_predictive = suite.predictive(die);
+    assert_ulps_eq!{prior_predictive.total(), 1.0, max_ulps = 4}
+    assert_ulps_eq!{prior_predictive.prob(&1, 0.0), 0.135, max_ulps = 4}
+    assert_ulps_eq!{prior_predictive.prob(&6, 0.0), 0.085, max_ulps = 4}
+    assert_ulps_eq!{prior_predictive.prob(&20, 0.0), 0.01, max_ulps = 4}
+    // the probability of a roll is the evidence it would give
+    assert_ulps_eq!{prior_predictive.prob(&7, 0.0), suite.evidence([7].iter()), max_ulps = 4}
+
+    suite.update(&6);
+    let predictive = suite.predictive(die);
+    assert_ulps_eq!{predictive.total(), 1.0, max_ulps = 4}
+    assert_ulps_eq!{predictive.prob(&7, 0.0), suite.evidence([7].iter()), max_ulps = 4}
+    assert_ulps_eq!{predictive.prob(&5, 0.0),
+                    0.3921568627450981 / 6.0 + 0.29411764705882354 / 8.0 +
+                    0.19607843137254904 / 12.0 + 0.11764705882352944 / 20.0,
+                    max_ulps = 4}
+    assert_ulps_eq!{predictive.prob(&21, 0.0), 0.0, max_ulps = 4}
+}
@@ -10,6 +10,7 @@ extern crate think_bayes;
 use think_bayes::pmf::*;
 use think_bayes::simulation::*;
 use think_bayes::utils::*;
+use think_bayes::cdf::*;
 #[macro_use]
 extern crate approx;
 
@@ -61,6 +62,27 @@ fn suite_dungeons_sum() {
     }
 }
 
+#[test]
+fn suite_dungeons_sum_ks() {
+    let d6 = Die::new(6);
+    let dice = [&d6; 3];
+    let sample_n = 100_000;
+    let mut three = sample_sum(dice.iter().map(|i| i.get_pdf()), sample_n);
+    three.normalize(1.0);
+    let three_exact = d6.get_pdf() + d6.get_pdf() + d6.get_pdf();
+    let sample_cdf = three.make_cdf();
+    let exact_cdf = three_exact.make_cdf();
+    // should fail less than .1% of the time
+    let statistic = sample_cdf.ks_statistic(&exact_cdf, sample_n as f64);
+    assert!{kolmogorov_p_value(statistic) > 0.001};
+    assert!{sample_cdf.wasserstein(&exact_cdf) < 0.05};
+
+    // a fourth die shifts the whole distribution up
+    let four_exact = &three_exact + d6.get_pdf();
+    assert!{four_exact.make_cdf().dominates(&exact_cdf)};
+    assert_relative_eq!{four_exact.make_cdf().wasserstein(&exact_cdf), 3.5, epsilon = 1e-9};
+}
+
 #[test]
 fn suite_dungeons_max() {
     let d6 = Die::new(6);
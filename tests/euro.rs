@@ -10,6 +10,10 @@ extern crate think_bayes;
 use think_bayes::pmf::*;
 use think_bayes::suite::*;
 use think_bayes::cdf::*;
+use think_bayes::evidence::*;
+use think_bayes::trace::*;
+use think_bayes::beta::*;
+use think_bayes::real::*;
 #[macro_use]
 extern crate approx;
 
@@ -42,6 +46,11 @@ impl Euro {
         out.pmf.normalize(1.0);
         out
     }
+    pub fn new_fair() -> Euro {
+        let mut out = Euro { pmf: Pmf::new() };
+        out.pmf.set(50, 1.0);
+        out
+    }
     pub fn new_triangle() -> Euro {
         let mut out = Euro { pmf: Pmf::new() };
         for v in 0..51 {
@@ -59,10 +68,16 @@ impl Euro {
     }
 }
 
-impl Suite<char, u8> for Euro {
+impl HasPmf<u8> for Euro {
+    fn get_pmf(&self) -> &Pmf<u8> {
+        &self.pmf
+    }
     fn get_mut_pmf(&mut self) -> &mut Pmf<u8> {
         &mut self.pmf
     }
+}
+
+impl Suite<char, u8> for Euro {
     /// Computes the likelihood of the data under the hypothesis.
     ///
     /// hypo: integer value of x, the probability of heads (0-100)
@@ -80,9 +95,6 @@ impl Suite<char, u8> for Euro {
 // wow we can reuse the same structue!
 
 impl Suite<(u16, u16), u8> for Euro {
-    fn get_mut_pmf(&mut self) -> &mut Pmf<u8> {
-        &mut self.pmf
-    }
     /// Computes the likelihood of the data under the hypothesis.
     ///
     ///     hypo: integer value of x, the probability of heads (0-100)
@@ -132,8 +144,8 @@ fn suite_euro() {
     assert_ulps_eq!{suite2.prob(&50, 0.0), 0.023847537214693623, max_ulps = 4};
     // 4.4  Optimization
     let mut suite3 = Euro::new_triangle();
-    suite3.update_set(['H'].iter().cycle().take(140));
-    suite3.update_set(['T'].iter().cycle().take(110));
+    suite3.update_set(['H'].iter().cycle().take(140)).unwrap();
+    suite3.update_set(['T'].iter().cycle().take(110)).unwrap();
     let suite3 = suite3.get_pdf();
     assert_eq!(suite3.maximum_likelihood(), &56);
     assert_ulps_eq!{suite3.mean(), 55.74349943859506, max_ulps = 4};
@@ -164,3 +176,273 @@ fn suite_euro() {
     }
 
 }
+
+#[test]
+fn suite_euro_evidence() {
+    // Is the coin biased or fair?
+    let heads_tails = [(140u16, 110u16)];
+    let fair = Euro::new_fair();
+    let uniform = Euro::new_uniform();
+    let triangle = Euro::new_triangle();
+    assert_relative_eq!{fair.evidence(heads_tails.iter()), 0.5f64.powi(250), max_relative = 1e-12};
+
+    let bf_uniform = bayes_factor(&uniform, &fair, heads_tails.iter());
+    let bf_triangle = bayes_factor(&triangle, &fair, heads_tails.iter());
+    // the data are evidence against the biased hypotheses, but not strong evidence
+    assert_relative_eq!{bf_uniform, 0.4720033259017429, max_relative = 1e-9};
+    assert_relative_eq!{bf_triangle, 0.8386610248238556, max_relative = 1e-9};
+
+    // the same thing one flip at a time, updating as we go
+    let mut models = ModelComparison::new();
+    let mut suites = [("fair", Euro::new_fair()),
+                      ("uniform", Euro::new_uniform()),
+                      ("triangle", Euro::new_triangle())];
+    for &mut (name, ref mut suite) in suites.iter_mut() {
+        models.add_model(name, 1.0);
+        for data in ['H'].iter().cycle().take(140).chain(['T'].iter().cycle().take(110)) {
+            models.observe(&name, suite.update(data));
+        }
+    }
+    assert_relative_eq!{models.bayes_factor(&"uniform", &"fair"), bf_uniform, max_relative = 1e-9};
+    assert_relative_eq!{models.bayes_factor(&"triangle", &"fair"), bf_triangle, max_relative = 1e-9};
+    let posterior = models.posterior();
+    assert_relative_eq!{posterior.prob(&"fair", 0.0),
+                        1.0 / (1.0 + bf_uniform + bf_triangle),
+                        max_relative = 1e-9};
+    assert!{posterior.prob(&"fair", 0.0) > posterior.prob(&"triangle", 0.0)};
+    assert!{posterior.prob(&"triangle", 0.0) > posterior.prob(&"uniform", 0.0)};
+
+    // or a whole dataset at a time, in log space
+    let mut log_models = ModelComparison::new();
+    let mut suites = [("fair", Euro::new_fair()), ("uniform", Euro::new_uniform())];
+    for &mut (name, ref mut suite) in suites.iter_mut() {
+        log_models.add_model(name, 1.0);
+        let flips = ['H'].iter().cycle().take(140).chain(['T'].iter().cycle().take(110));
+        log_models.observe_log(&name, suite.update_set(flips).unwrap().log_evidence);
+    }
+    assert_relative_eq!{log_models.bayes_factor(&"uniform", &"fair"), bf_uniform, max_relative = 1e-9};
+}
+
+#[test]
+fn suite_euro_long_dataset() {
+    // 0.5^10000 underflows, so update_set has to renormalize along the way
+    let mut suite = Euro::new_uniform();
+    let flips = ['H', 'T'];
+    let stats = suite.update_set(flips.iter().cycle().take(10_000)).unwrap();
+    assert!{stats.renormalizations > 0};
+    // 0 and 100 are ruled out, the far tails underflow relative to 50
+    assert!{stats.eliminated > 2};
+    assert_eq!{stats.eliminated,
+               suite.get_pdf().items().iter().filter(|&&(_, p)| p == 0.0).count()};
+    assert!{stats.log_evidence.is_finite()};
+    assert!{stats.log_evidence < -6900.0};
+    assert_eq!{suite.get_pdf().maximum_likelihood(), &50};
+    assert_ulps_eq!{suite.get_pdf().total(), 1.0, max_ulps = 4};
+
+    // the evidence agrees with the sequential normalizing constants
+    let mut suite2 = Euro::new_uniform();
+    let log_evidence = flips.iter()
+        .cycle()
+        .take(10_000)
+        .fold(0.0, |s, data| s + suite2.update(data).ln());
+    assert_relative_eq!{stats.log_evidence, log_evidence, max_relative = 1e-9};
+
+    // and so does the log evidence, which leaves the suite alone
+    let uniform = Euro::new_uniform();
+    let long = flips.iter().cycle().take(10_000);
+    assert_eq!{uniform.evidence(long.clone()), 0.0};
+    assert_relative_eq!{uniform.log_evidence(long.clone()), stats.log_evidence, max_relative = 1e-12};
+    assert_ulps_eq!{uniform.get_pdf().prob(&50, 0.0), 1.0 / 101.0, max_ulps = 4};
+    // the raw evidence is 0 / 0, the Bayes factor is not
+    let bf = bayes_factor(&uniform, &Euro::new_fair(), long);
+    assert_relative_eq!{bf, (stats.log_evidence - 10_000.0 * 0.5f64.ln()).exp(), max_relative = 1e-9};
+    assert!{0.0 < bf && bf < 1.0};
+}
+
+#[test]
+fn suite_euro_impossible_data() {
+    let mut suite = Euro { pmf: Pmf::new() };
+    suite.pmf.set(0, 0.5);
+    suite.pmf.set(100, 0.5);
+    assert_eq!{suite.update_set(['H', 'T'].iter()).err(), Some(SuiteError::ImpossibleData)};
+}
+
+#[test]
+fn suite_euro_trace() {
+    // 4.3  Swamping the priors, one flip at a time
+    fn summarize(pmf: &Pmf<u8>) -> Vec<f64> {
+        vec![pmf.mean(), (*pmf.maximum_likelihood()).into()]
+    }
+    let flips: Vec<char> = ['H'].iter()
+        .cycle()
+        .take(140)
+        .chain(['T'].iter().cycle().take(110))
+        .cloned()
+        .collect();
+    let mut uniform = Recorder::new(Euro::new_uniform(), 50, &["mean", "map"], summarize);
+    let mut triangle = Recorder::new(Euro::new_triangle(), 0, &["mean", "map"], summarize);
+    uniform.update_each(flips.iter());
+    triangle.update_each(flips.iter());
+
+    assert_eq!{uniform.trace().len(), 251};
+    assert_ulps_eq!{uniform.trace()[0].summary[0], 50.0, max_ulps = 4};
+    assert_ulps_eq!{uniform.trace()[250].summary[0], 55.952380952380935, max_ulps = 4};
+    assert_ulps_eq!{triangle.trace()[250].summary[0], 55.74349943859506, max_ulps = 4};
+    assert_ulps_eq!{uniform.trace()[250].summary[1], 56.0, max_ulps = 4};
+    // the evidence of each step is the normalizing constant of that update
+    assert_ulps_eq!{uniform.trace()[1].evidence, 0.5, max_ulps = 4};
+
+    let steps: Vec<usize> = uniform.snapshots().iter().map(|&(step, _)| step).collect();
+    assert_eq!{steps, vec![0, 50, 100, 150, 200, 250]};
+    assert_eq!{uniform.snapshots()[5].1.maximum_likelihood(), &56};
+    assert!{triangle.snapshots().is_empty()};
+
+    let mut csv = Vec::new();
+    uniform.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!{lines.len(), 252};
+    assert_eq!{lines[0], "step,evidence,mean,map"};
+    // the map of a flat prior is whichever tie comes first
+    assert!{lines[1].starts_with("0,1,50,")};
+    assert!{lines[251].starts_with("250,")};
+    assert!{lines[251].ends_with(",56")};
+
+    // one step per batch of flips
+    let mut batches = Recorder::new(Euro::new_uniform(), 1, &["mean", "map"], summarize);
+    for batch in flips.chunks(50) {
+        batches.update_set(batch.iter()).unwrap();
+    }
+    assert_eq!{batches.trace().len(), 6};
+    assert_relative_eq!{batches.trace()[5].summary[0], 55.952380952380935, max_relative = 1e-12};
+    let first_fifty = uniform.trace()[1..51].iter().fold(1.0, |s, point| s * point.evidence);
+    assert_relative_eq!{batches.trace()[1].evidence, first_fifty, max_relative = 1e-9};
+    assert_eq!{batches.snapshots().len(), 6};
+
+    // an impossible batch is not recorded
+    let mut extremes = Euro { pmf: Pmf::new() };
+    extremes.pmf.set(0, 0.5);
+    extremes.pmf.set(100, 0.5);
+    let mut extremes = Recorder::new(extremes, 1, &["mean", "map"], summarize);
+    assert_eq!{extremes.update_set(['H', 'T'].iter()).err(), Some(SuiteError::ImpossibleData)};
+    assert_eq!{extremes.trace().len(), 1};
+    assert_ulps_eq!{extremes.suite().get_pdf().prob(&0, 0.0), 0.5, max_ulps = 4};
+}
+
+#[test]
+fn suite_euro_grouped() {
+    use std::cell::Cell;
+    // 4.4  Optimization, without writing a second likelihood
+    let calls = Cell::new(0);
+    let mut suite = FnSuite::new(Euro::new_triangle().pmf, |&data: &char, &hypo: &u8| {
+        calls.set(calls.get() + 1);
+        let x = f64::from(hypo) / 100.0;
+        if data == 'H' { x } else { 1.0 - x }
+    });
+    let flips: Vec<char> = ['H'].iter()
+        .cycle()
+        .take(140)
+        .chain(['T'].iter().cycle().take(110))
+        .cloned()
+        .collect();
+    let stats = suite.update_grouped(flips.iter()).unwrap();
+    // one call for 'H' and one for 'T' per hypothesis,
+    // except 0 and 100 which the triangle prior already ruled out
+    assert_eq!{calls.get(), 2 * 99};
+    assert_eq!{stats.eliminated, 0};
+
+    let mut suite2 = Euro::new_triangle();
+    let stats2 = suite2.update_set(flips.iter()).unwrap();
+    assert_relative_eq!{stats.log_evidence, stats2.log_evidence, max_relative = 1e-12};
+    for hypo in suite2.get_pdf().values() {
+        assert_relative_eq!{suite.get_pmf().prob(&hypo, 0.0),
+                            suite2.get_pdf().prob(&hypo, 0.0),
+                            epsilon = 1e-15,
+                            max_relative = 1e-9};
+    }
+    assert_ulps_eq!{suite.get_pmf().mean(), 55.74349943859506, max_ulps = 64};
+
+    // counts much too big for likelihood^count to be represented
+    let mut suite3 = Euro::new_uniform();
+    let stats3 = suite3.update_counts([('H', 14_000), ('T', 11_000)].iter().map(|&(ref d, n)| (d, n)))
+        .unwrap();
+    assert!{stats3.log_evidence.is_finite()};
+    assert_eq!{suite3.get_pdf().maximum_likelihood(), &56};
+}
+
+#[test]
+fn suite_euro_drift() {
+    // tempering by 2 is the same as seeing the data twice
+    let mut twice = Euro::new_uniform();
+    twice.update(&'H');
+    twice.update(&'H');
+    let mut tempered = Euro::new_uniform();
+    tempered.update_tempered(&'H', 2.0);
+    assert_relative_eq!{tempered.get_pdf().mean(), twice.get_pdf().mean(), max_relative = 1e-12};
+    tempered.update_tempered(&'T', 0.0);
+    assert_relative_eq!{tempered.get_pdf().mean(), twice.get_pdf().mean(), max_relative = 1e-12};
+
+    // a coin that changes from mostly heads to mostly tails
+    let flips: Vec<char> = ['H'].iter()
+        .cycle()
+        .take(80)
+        .chain(['T'].iter().cycle().take(20))
+        .chain(['H'].iter().cycle().take(20))
+        .chain(['T'].iter().cycle().take(80))
+        .cloned()
+        .collect();
+    let mut even = Euro::new_uniform();
+    even.update_set_weighted(flips.iter().map(|d| (d, 1.0))).unwrap();
+    let mut plain = Euro::new_uniform();
+    plain.update_set(flips.iter()).unwrap();
+    assert_relative_eq!{even.get_pdf().mean(), plain.get_pdf().mean(), max_relative = 1e-12};
+    assert_relative_eq!{even.get_pdf().mean(), 50.0, max_relative = 1e-12};
+
+    // older flips count for less, so the estimate follows the recent tails
+    let n = flips.len();
+    let mut weighted = Euro::new_uniform();
+    weighted.update_set_weighted(flips.iter().enumerate().map(|(i, d)| (d, 0.98f64.powi((n - i) as i32))))
+        .unwrap();
+    assert!{weighted.get_pdf().mean() < 35.0};
+
+    // forgetting between the two batches does the same
+    let mut forgetful = Euro::new_uniform();
+    forgetful.update_set(flips[..100].iter()).unwrap();
+    forgetful.discount(0.9, &Euro::new_uniform().pmf);
+    forgetful.update_set(flips[100..].iter()).unwrap();
+    assert!{forgetful.get_pdf().mean() < 35.0};
+
+    // discounting all the way goes back to the target
+    forgetful.discount_flat(1.0);
+    assert_relative_eq!{forgetful.get_pdf().prob(&7, 0.0), 1.0 / 101.0, max_relative = 1e-12};
+    assert_relative_eq!{forgetful.get_pdf().total(), 1.0, max_relative = 1e-12};
+    let mut fair = Euro::new_triangle();
+    fair.discount(1.0, &Euro::new_fair().pmf);
+    assert_eq!{fair.get_pdf().prob(&50, 0.0), 1.0};
+}
+
+#[test]
+fn suite_euro_beta() {
+    // 4.5  The beta distribution
+    let mut suite = Euro::new_uniform();
+    suite.update(&(140, 110));
+    let mut beta = Beta::new(1.0, 1.0);
+    beta.update((140, 110));
+    assert_ulps_eq!{beta.mean(), 141.0 / 252.0, max_ulps = 4};
+    assert_relative_eq!{beta.mean() * 100.0, suite.get_pdf().mean(), max_relative = 1e-3};
+
+    // on the same grid it is the same posterior as the suite
+    let pmf = beta.make_pmf(101);
+    assert_eq!(pmf.values().len(), suite.get_pdf().values().len());
+    for (x, &p) in pmf.iter() {
+        let hypo = (x.get() * 100.0).round() as u8;
+        assert_relative_eq!{p, suite.get_pdf().prob(&hypo, 0.0), max_relative = 1e-9, epsilon = 1e-300};
+    }
+    assert_eq!(pmf.maximum_likelihood(), &Real::new(0.56));
+    assert_ulps_eq!{beta.mode(), 0.56, max_ulps = 4};
+    // the exact Cdf puts the mass between grid points on the point above
+    let cdf = beta.make_cdf(101);
+    assert_eq!(cdf.credible_interval(90.0), (Real::new(0.51), Real::new(0.62)));
+    let cdf: Cdf<_> = (&pmf).into();
+    assert_eq!(cdf.credible_interval(90.0), (Real::new(0.51), Real::new(0.61)));
+}
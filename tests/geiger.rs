@@ -0,0 +1,194 @@
+// This file contains code for use with "Think Bayes",
+// by Allen B. Downey, available from greenteapress.com
+//
+// Copyright 2012 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+extern crate think_bayes;
+use think_bayes::pmf::*;
+use think_bayes::suite::*;
+use think_bayes::hierarchy::*;
+use think_bayes::special::*;
+use think_bayes::poisson::*;
+#[macro_use]
+extern crate approx;
+
+/// Computes the binomial pmf, in log space so big n do not overflow.
+fn eval_binomial_pmf(k: u32, n: u32, p: f64) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (ln_factorial(n) - ln_factorial(k) - ln_factorial(n - k) + f64::from(k) * p.ln() +
+     f64::from(n - k) * (1.0 - p).ln())
+        .exp()
+}
+
+/// Represents hypotheses about n, the number of particles that hit the counter.
+struct Detector {
+    pmf: Pmf<u32>,
+    f: f64,
+}
+
+impl Detector {
+    /// r: the emission rate, in particles per second
+    /// f: the fraction of particles that get counted
+    /// step: the distance between the hypotheses about n
+    pub fn new(r: f64, f: f64, step: u32) -> Detector {
+        Detector {
+            pmf: make_poisson_pmf(r, 500, step),
+            f,
+        }
+    }
+}
+
+impl HasPmf<u32> for Detector {
+    fn get_pmf(&self) -> &Pmf<u32> {
+        &self.pmf
+    }
+    fn get_mut_pmf(&mut self) -> &mut Pmf<u32> {
+        &mut self.pmf
+    }
+}
+
+impl Suite<u32, u32> for Detector {
+    /// Computes the likelihood of the data under the hypothesis.
+    /// hypo: number of particles that hit the counter, n
+    /// data: number of particles counted, k
+    fn likelihood(&self, &k: &u32, &n: &u32) -> f64 {
+        eval_binomial_pmf(k, n, self.f)
+    }
+}
+
+/// Represents hypotheses about r, with a Detector for each.
+fn emitter(rs: &[u32], f: f64) -> Hierarchical<u32, u32, Detector> {
+    Hierarchical::new(rs.iter().map(|&r| (r, Detector::new(f64::from(r), f, 5))))
+}
+
+#[test]
+fn suite_geiger_detector() {
+    // 14.1 - 14.3  The Geiger counter problem, with r known
+    let mut suite = Detector::new(250.0, 0.1, 1);
+    assert_relative_eq!{suite.update(&15), 0.00989096058846494, max_relative = 1e-9};
+    assert_relative_eq!{suite.get_pmf().mean(), 240.0, max_relative = 1e-9};
+}
+
+#[test]
+fn suite_geiger_emitter() {
+    // 14.4 - 14.5  Hierarchical Bayes
+    let rs: Vec<u32> = (1..501).step_by(5).collect();
+    let mut suite = emitter(&rs, 0.1);
+    let evidence = suite.evidence([15].iter());
+    assert_relative_eq!{suite.update(&15), evidence, max_relative = 1e-9};
+    assert_relative_eq!{evidence, 0.020000000134225288, max_relative = 1e-9};
+
+    // the distribution of r, the top level
+    let dist_of_r = suite.get_pmf();
+    assert_relative_eq!{dist_of_r.mean(), 160.00000185229283, max_relative = 1e-9};
+    assert_eq!{dist_of_r.maximum_likelihood(), &151};
+    // the distribution of n, the marginal of the lower level
+    let dist_of_n = suite.marginal();
+    assert_relative_eq!{dist_of_n.mean(), 159.00000164216544, max_relative = 1e-9};
+    assert_eq!{dist_of_n.maximum_likelihood(), &150};
+    // each detector was updated too
+    let mut detector = Detector::new(151.0, 0.1, 5);
+    detector.update(&15);
+    assert_relative_eq!{suite.child(&151).unwrap().get_pmf().prob(&150, 0.0),
+                        detector.get_pmf().prob(&150, 0.0),
+                        max_relative = 1e-12};
+
+    // two counts at once is the same as one after the other
+    let mut once = emitter(&rs, 0.1);
+    let stats = once.update_set([15, 20].iter()).unwrap();
+    let mut twice = emitter(&rs, 0.1);
+    let first = twice.update(&15);
+    let second = twice.update(&20);
+    assert_relative_eq!{stats.log_evidence, (first * second).ln(), max_relative = 1e-9};
+    assert_relative_eq!{once.marginal().mean(), twice.marginal().mean(), max_relative = 1e-9};
+    let mut grouped = emitter(&rs, 0.1);
+    grouped.update_grouped([15, 20].iter()).unwrap();
+    assert_relative_eq!{grouped.get_pmf().mean(), once.get_pmf().mean(), max_relative = 1e-9};
+}
+
+#[test]
+fn suite_geiger_deeper() {
+    // a population of sensors: the efficiency f is unknown too
+    let rs: Vec<u32> = (1..501).step_by(25).collect();
+    let mut suite = Hierarchical::new([(1, emitter(&rs, 0.1)), (2, emitter(&rs, 0.2))]);
+    let flat = suite.get_pmf().clone();
+    let evidence = suite.update(&15);
+    let low = emitter(&rs, 0.1).evidence([15].iter());
+    let high = emitter(&rs, 0.2).evidence([15].iter());
+    assert_relative_eq!{evidence, (low + high) / 2.0, max_relative = 1e-9};
+    assert_relative_eq!{suite.get_pmf().prob(&1, 0.0), low / (low + high), max_relative = 1e-9};
+    assert!{suite.get_pmf() != &flat};
+    // the marginal posterior at each level
+    let dist_of_r = suite.marginal();
+    let dist_of_n = suite.marginal_by(|emitter| emitter.marginal());
+    assert_relative_eq!{dist_of_r.total(), 1.0, max_relative = 1e-12};
+    assert_relative_eq!{dist_of_n.total(), 1.0, max_relative = 1e-12};
+    assert!{dist_of_n.mean() < dist_of_r.mean()};
+}
+
+#[test]
+fn suite_geiger_impossible() {
+    // more particles than any detector could see leaves the whole hierarchy as it was
+    let rs: Vec<u32> = (1..501).step_by(25).collect();
+    let mut suite = emitter(&rs, 0.1);
+    suite.update(&15);
+    let top = suite.get_pmf().clone();
+    let child = suite.child(&151).unwrap().get_pmf().clone();
+    assert_eq!{suite.update_set([20, 100_000].iter()).err(), Some(SuiteError::ImpossibleData)};
+    assert!{suite.get_pmf() == &top};
+    assert!{suite.child(&151).unwrap().get_pmf() == &child};
+    assert!{suite.update_set([20].iter()).is_ok()};
+}
+
+#[test]
+fn suite_geiger_tempered() {
+    // tempering reaches the detectors too, so the marginal stays consistent
+    let rs: Vec<u32> = (1..501).step_by(25).collect();
+    let mut tempered = emitter(&rs, 0.1);
+    let evidence = tempered.update_tempered(&15, 2.0);
+    // the same count seen twice
+    let mut twice = emitter(&rs, 0.1);
+    let first = twice.update(&15);
+    let second = twice.update(&15);
+    assert_relative_eq!{evidence, first * second, max_relative = 1e-9};
+    assert_relative_eq!{tempered.get_pmf().mean(), twice.get_pmf().mean(), max_relative = 1e-9};
+    assert_relative_eq!{tempered.marginal().mean(), twice.marginal().mean(), max_relative = 1e-9};
+
+    let mut weighted = emitter(&rs, 0.1);
+    weighted.update_set_weighted([(&15, 1.0), (&20, 0.5)].iter().cloned()).unwrap();
+    let mut stepped = emitter(&rs, 0.1);
+    stepped.update(&15);
+    stepped.update_tempered(&20, 0.5);
+    assert_relative_eq!{weighted.marginal().mean(), stepped.marginal().mean(), max_relative = 1e-9};
+    assert_relative_eq!{weighted.child(&151).unwrap().get_pmf().mean(),
+                        stepped.child(&151).unwrap().get_pmf().mean(),
+                        max_relative = 1e-9};
+}
+
+#[test]
+#[should_panic(expected = "can not be discounted")]
+fn suite_geiger_discount() {
+    let rs: Vec<u32> = (1..501).step_by(25).collect();
+    let mut suite = emitter(&rs, 0.1);
+    suite.update(&15);
+    suite.discount_flat(0.1);
+}
+
+#[test]
+fn suite_geiger_long_run() {
+    // the evidence of 400 counts underflows, the log evidence does not
+    let rs: Vec<u32> = (1..501).step_by(25).collect();
+    let suite = emitter(&rs, 0.1);
+    let counts = [15; 400];
+    assert_eq!{suite.evidence(counts.iter()), 0.0};
+    let log_evidence = suite.log_evidence(counts.iter());
+    assert!{log_evidence.is_finite()};
+    let mut updated = emitter(&rs, 0.1);
+    let stats = updated.update_set(counts.iter()).unwrap();
+    assert_relative_eq!{log_evidence, stats.log_evidence, max_relative = 1e-9};
+}
@@ -0,0 +1,75 @@
+// This file contains code for use with "Think Bayes",
+// by Allen B. Downey, available from greenteapress.com
+//
+// Copyright 2012 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+extern crate think_bayes;
+use think_bayes::pmf::*;
+use think_bayes::suite::*;
+use think_bayes::real::*;
+use think_bayes::utils::*;
+use think_bayes::gaussian::*;
+use think_bayes::poisson::*;
+#[macro_use]
+extern crate approx;
+
+// Goals scored in the first four games of the 2011 Stanley Cup final.
+const BRUINS: [u32; 4] = [0, 2, 8, 4];
+const CANUCKS: [u32; 4] = [1, 3, 1, 0];
+
+/// Represents hypotheses about the scoring rate for a team.
+fn hockey() -> FnSuite<u32, Real, fn(&u32, &Real) -> f64> {
+    fn likelihood(&goals: &u32, lam: &Real) -> f64 {
+        eval_poisson_pmf(goals, lam.get())
+    }
+    FnSuite::new(make_gaussian_pmf(2.8, 0.3, 4.0, 101), likelihood)
+}
+
+/// Computes the probability that a value from pmf1 is less than a value from pmf2.
+fn prob_less<V: Eq + std::hash::Hash + Clone + Ord>(pmf1: &Pmf<V>, pmf2: &Pmf<V>) -> f64 {
+    let mut total = 0.0;
+    for (v1, p1) in pmf1.iter() {
+        for (v2, p2) in pmf2.iter() {
+            if v1 < v2 {
+                total += p1 * p2;
+            }
+        }
+    }
+    total
+}
+
+#[test]
+fn suite_hockey() {
+    // 7.2 - 7.4  The Boston Bruins problem, updating the goal scoring rate
+    let mut bruins = hockey();
+    bruins.update_set(BRUINS.iter()).unwrap();
+    let mut canucks = hockey();
+    canucks.update_set(CANUCKS.iter()).unwrap();
+    assert_relative_eq!{bruins.get_pmf().mean(), 2.8814477910015497, max_relative = 1e-12};
+    assert_relative_eq!{canucks.get_pmf().mean(), 2.614520576110951, max_relative = 1e-12};
+
+    // 7.5  The distribution of goals
+    let goals = |lam: &Real| make_poisson_pmf(lam.get(), 10, 1);
+    let bruins_goals = make_predictive(bruins.get_pmf(), goals);
+    let canucks_goals = make_predictive(canucks.get_pmf(), goals);
+    assert_ulps_eq!{bruins_goals.total(), 1.0, max_ulps = 4};
+
+    // 7.6  The probability of winning
+    let p_win = prob_less(&canucks_goals, &bruins_goals);
+    let p_loss = prob_less(&bruins_goals, &canucks_goals);
+    let p_tie = 1.0 - p_win - p_loss;
+    assert_relative_eq!{p_win, 0.45799978231172506, max_relative = 1e-10};
+    assert_relative_eq!{p_loss, 0.3702903260410743, max_relative = 1e-10};
+    assert_relative_eq!{p_tie, 0.17170989164720077, max_relative = 1e-10};
+
+    // 7.7  Sudden death, the time until the next goal
+    let times = |lam: &Real| make_exponential_pmf(lam.get(), 2.0, 2001);
+    let bruins_time = make_predictive(bruins.get_pmf(), times);
+    let canucks_time = make_predictive(canucks.get_pmf(), times);
+    let p_overtime = prob_less(&bruins_time, &canucks_time);
+    assert_relative_eq!{p_overtime, 0.522784786867728, max_relative = 1e-10};
+    assert_relative_eq!{p_win + p_tie * p_overtime, 0.5477671014195876, max_relative = 1e-10};
+}
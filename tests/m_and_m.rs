@@ -53,10 +53,16 @@ impl<V: Eq + Hash + Copy> M_and_M<V> {
     }
 }
 
-impl<V: Eq + Hash + Copy> Suite<(V, V), V> for M_and_M<V> {
+impl<V: Eq + Hash + Copy> HasPmf<V> for M_and_M<V> {
+    fn get_pmf(&self) -> &Pmf<V> {
+        &self.pmf
+    }
     fn get_mut_pmf(&mut self) -> &mut Pmf<V> {
         &mut self.pmf
     }
+}
+
+impl<V: Eq + Hash + Copy> Suite<(V, V), V> for M_and_M<V> {
     fn likelihood(&self, data: &(V, V), hypo: &V) -> f64 {
         let &(bag, color) = data;
         self.hypotheses[hypo][&bag][&color]
@@ -104,3 +110,69 @@ fn suite_m_and_m() {
     assert_ulps_eq!{suite.get_pdf().prob(&"A", 0.0), 0.7407407407407407, max_ulps = 4}
     assert_ulps_eq!{suite.get_pdf().prob(&"B", 0.0), 0.2592592592592592, max_ulps = 4}
 }
+
+/// A hypothesis that owns its data, which needs `Suite` to allow non-Copy hypotheses.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Assignment {
+    name: String,
+    bag1: Vec<(String, u32)>,
+    bag2: Vec<(String, u32)>,
+}
+
+impl Assignment {
+    fn new(name: &str, bag1: &[(&str, u32)], bag2: &[(&str, u32)]) -> Assignment {
+        let own = |mix: &[(&str, u32)]| mix.iter().map(|&(c, n)| (c.to_string(), n)).collect();
+        Assignment {
+            name: name.to_string(),
+            bag1: own(bag1),
+            bag2: own(bag2),
+        }
+    }
+}
+
+struct OwnedMAndM {
+    pmf: Pmf<Assignment>,
+}
+
+impl HasPmf<Assignment> for OwnedMAndM {
+    fn get_pmf(&self) -> &Pmf<Assignment> {
+        &self.pmf
+    }
+    fn get_mut_pmf(&mut self) -> &mut Pmf<Assignment> {
+        &mut self.pmf
+    }
+}
+
+impl Suite<(&'static str, &'static str), Assignment> for OwnedMAndM {
+    fn likelihood(&self, &(bag, color): &(&'static str, &'static str), hypo: &Assignment) -> f64 {
+        let mix = if bag == "bag1" { &hypo.bag1 } else { &hypo.bag2 };
+        mix.iter().find(|&(c, _)| c == color).map_or(0.0, |&(_, n)| n.into())
+    }
+}
+
+#[test]
+fn suite_m_and_m_owned_hypotheses() {
+    let mix94 = [("brown", 30), ("yellow", 20), ("red", 20), ("green", 10), ("orange", 10),
+                 ("tan", 10)];
+    let mix96 = [("blue", 24), ("green", 20), ("orange", 16), ("yellow", 14), ("red", 13),
+                 ("brown", 13)];
+    let hypo_a = Assignment::new("A", &mix94, &mix96);
+    let hypo_b = Assignment::new("B", &mix96, &mix94);
+
+    let mut suite = OwnedMAndM { pmf: Pmf::new() };
+    suite.pmf.set(hypo_a.clone(), 1.0);
+    suite.pmf.set(hypo_b.clone(), 1.0);
+    suite.pmf.normalize(1.0);
+
+    suite.update(&("bag1", "yellow"));
+    suite.update(&("bag2", "green"));
+    assert_ulps_eq!{suite.get_pmf().prob(&hypo_a, 0.0), 0.7407407407407407, max_ulps = 4}
+    assert_ulps_eq!{suite.get_pmf().prob(&hypo_b, 0.0), 0.2592592592592592, max_ulps = 4}
+    assert_eq!{suite.get_pmf().maximum_likelihood().name, "A"}
+
+    let mut suite2 = OwnedMAndM { pmf: Pmf::new() };
+    suite2.pmf.set(hypo_a.clone(), 1.0);
+    suite2.pmf.set(hypo_b.clone(), 1.0);
+    suite2.update_set([("bag1", "yellow"), ("bag2", "green")].iter()).unwrap();
+    assert_ulps_eq!{suite2.get_pmf().prob(&hypo_a, 0.0), 0.7407407407407407, max_ulps = 4}
+}
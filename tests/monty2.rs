@@ -32,10 +32,16 @@ impl Monty {
     }
 }
 
-impl Suite<char, char> for Monty {
+impl HasPmf<char> for Monty {
+    fn get_pmf(&self) -> &Pmf<char> {
+        &self.pmf
+    }
     fn get_mut_pmf(&mut self) -> &mut Pmf<char> {
         &mut self.pmf
     }
+}
+
+impl Suite<char, char> for Monty {
     fn likelihood(&self, data: &char, hypo: &char) -> f64 {
         if data == hypo {
             0.0
@@ -0,0 +1,118 @@
+// This file contains code for use with "Think Bayes",
+// by Allen B. Downey, available from greenteapress.com
+//
+// Copyright 2012 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+extern crate think_bayes;
+use think_bayes::pmf::*;
+use think_bayes::suite::*;
+use think_bayes::joint::*;
+#[macro_use]
+extern crate approx;
+
+/// Computes strafing speed, given location of shooter and impact.
+/// alpha: x location of shooter
+/// beta: y location of shooter
+/// x: location of impact
+/// Returns: derivative of x with respect to theta
+fn strafing_speed(alpha: u32, beta: u32, x: u32) -> f64 {
+    let beta = f64::from(beta);
+    let theta = (f64::from(x) - f64::from(alpha)).atan2(beta);
+    beta / theta.cos().powi(2)
+}
+
+/// Makes a Pmf of locations of impact, given shooter location.
+fn make_location_pmf(alpha: u32, beta: u32, locations: &[u32]) -> Pmf<u32> {
+    let mut pmf = Pmf::new();
+    for &x in locations {
+        pmf.set(x, 1.0 / strafing_speed(alpha, beta, x));
+    }
+    pmf.normalize(1.0);
+    pmf
+}
+
+/// Represents hypotheses about the location of an opponent.
+struct Paintball {
+    joint: Joint<(u32, u32)>,
+    locations: Vec<u32>,
+}
+
+impl Paintball {
+    pub fn new(alphas: &[u32], betas: &[u32], locations: &[u32]) -> Paintball {
+        let mut joint = Joint::new();
+        for &alpha in alphas {
+            for &beta in betas {
+                joint.get_mut_pmf().set((alpha, beta), 1.0);
+            }
+        }
+        joint.get_mut_pmf().normalize(1.0);
+        Paintball {
+            joint,
+            locations: locations.to_vec(),
+        }
+    }
+}
+
+impl HasPmf<(u32, u32)> for Paintball {
+    fn get_pmf(&self) -> &Pmf<(u32, u32)> {
+        self.joint.get_pmf()
+    }
+    fn get_mut_pmf(&mut self) -> &mut Pmf<(u32, u32)> {
+        self.joint.get_mut_pmf()
+    }
+}
+
+impl Suite<u32, (u32, u32)> for Paintball {
+    /// Computes the likelihood of the data under the hypothesis.
+    /// hypo: pair of alpha, beta
+    /// data: location of a hit
+    fn likelihood(&self, &x: &u32, &(alpha, beta): &(u32, u32)) -> f64 {
+        make_location_pmf(alpha, beta, &self.locations).prob(&x, 0.0)
+    }
+}
+
+#[test]
+fn suite_paintball() {
+    // 9.1 - 9.5  The paintball problem
+    let alphas: Vec<u32> = (0..31).collect();
+    let betas: Vec<u32> = (1..51).collect();
+    let locations: Vec<u32> = (0..31).collect();
+    let mut suite = Paintball::new(&alphas, &betas, &locations);
+    suite.update_set([15, 16, 18, 21].iter()).unwrap();
+
+    let marginal_alpha = suite.joint.marginal::<0>();
+    let marginal_beta = suite.joint.marginal::<1>();
+    assert_relative_eq!{marginal_alpha.mean(), 17.035211011480097, max_relative = 1e-12};
+    assert_relative_eq!{marginal_beta.mean(), 18.785713764935615, max_relative = 1e-12};
+    assert_eq!{marginal_alpha.maximum_likelihood(), &17};
+    assert_eq!{marginal_beta.maximum_likelihood(), &2};
+
+    // 9.6  Conditional distributions
+    let close = suite.joint.conditional::<0, 1>(&10);
+    let far = suite.joint.conditional::<0, 1>(&40);
+    assert_relative_eq!{close.mean(), 18.13377284586253, max_relative = 1e-12};
+    assert_relative_eq!{far.mean(), 15.88382800184165, max_relative = 1e-12};
+
+    // credible regions for a contour plot
+    let region = suite.joint.max_like_interval(25.0);
+    assert_eq!{region.len(), 29};
+    assert_eq!{region[0], &(17, 2)};
+    assert!{region.iter().all(|&&(alpha, beta)| (15..=20).contains(&alpha) && beta <= 6)};
+    assert_eq!{suite.joint.max_like_interval(50.0).len(), 232};
+    assert_eq!{suite.joint.max_like_interval(75.0).len(), 716};
+
+    // the prior was the product of two uniform distributions, the posterior is not
+    let prior = Paintball::new(&alphas, &betas, &locations);
+    let (alpha, beta) = prior.joint.to_independent();
+    let product = Joint::from_independent(&alpha, &beta);
+    assert_relative_eq!{product.get_pmf().prob(&(3, 4), 0.0),
+                        prior.get_pmf().prob(&(3, 4), 0.0),
+                        max_relative = 1e-12};
+    let (alpha, beta) = suite.joint.to_independent();
+    let product = Joint::from_independent(&alpha, &beta);
+    assert!{(product.get_pmf().prob(&(17, 2), 0.0) - suite.get_pmf().prob(&(17, 2), 0.0)).abs() >
+            1e-3};
+}
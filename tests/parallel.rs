@@ -0,0 +1,89 @@
+// This file contains code for use with "Think Bayes",
+// by Allen B. Downey, available from greenteapress.com
+//
+// Copyright 2012 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+// run with `cargo test --features parallel`
+#![cfg(feature = "parallel")]
+
+extern crate think_bayes;
+use think_bayes::pmf::*;
+use think_bayes::suite::*;
+use think_bayes::parallel::*;
+#[macro_use]
+extern crate approx;
+
+// This file uses composition to emulate classes
+struct Train {
+    pmf: Pmf<u32>,
+}
+
+impl Train {
+    pub fn new<I: Iterator<Item = u32>>(hypos: I, alpha: f64) -> Train {
+        let mut out = Train { pmf: Pmf::new() };
+        for v in hypos {
+            let hypo: f64 = v.into();
+            out.pmf.set(v, hypo.powf(-alpha));
+        }
+
+        out.pmf.normalize(1.0);
+        out
+    }
+}
+
+impl HasPmf<u32> for Train {
+    fn get_pmf(&self) -> &Pmf<u32> {
+        &self.pmf
+    }
+    fn get_mut_pmf(&mut self) -> &mut Pmf<u32> {
+        &mut self.pmf
+    }
+}
+
+impl Suite<u32, u32> for Train {
+    fn likelihood(&self, data: &u32, hypo: &u32) -> f64 {
+        if hypo < data {
+            0.0
+        } else {
+            1.0 / (*hypo as f64)
+        }
+    }
+}
+
+#[test]
+fn suite_train_par_update() {
+    let mut serial = Train::new(1..100_001, 1.0);
+    let mut parallel = Train::new(1..100_001, 1.0);
+    for data in &[60, 30, 90] {
+        assert_relative_eq!{serial.update(data), parallel.par_update(data), max_relative = 1e-12};
+    }
+    assert_relative_eq!{serial.get_pmf().mean(), parallel.get_pmf().mean(), max_relative = 1e-12};
+    for hypo in &[59, 90, 91, 1000, 100_000] {
+        assert_relative_eq!{serial.get_pmf().prob(hypo, 0.0),
+                            parallel.get_pmf().prob(hypo, 0.0),
+                            max_relative = 1e-12};
+    }
+}
+
+#[test]
+fn suite_train_par_update_set() {
+    let dataset: Vec<u32> = (0..500).map(|i| 100 + i % 400).collect();
+    let mut serial = Train::new(1..10_001, 1.0);
+    let mut parallel = Train::new(1..10_001, 1.0);
+    let stats = serial.update_set(dataset.iter()).unwrap();
+    let par_stats = parallel.par_update_set(dataset.iter()).unwrap();
+    assert_relative_eq!{stats.log_evidence, par_stats.log_evidence, max_relative = 1e-9};
+    assert_eq!{par_stats.renormalizations, 0};
+    // every train below 499 is ruled out, how far the underflow reaches into
+    // the long tail depends on when each one renormalizes
+    assert!{stats.eliminated >= 498};
+    assert!{par_stats.eliminated >= 498};
+    assert_relative_eq!{serial.get_pmf().mean(), parallel.get_pmf().mean(), max_relative = 1e-9};
+    assert_eq!{parallel.get_pmf().maximum_likelihood(), &499};
+
+    let mut impossible = Train::new(1..11, 1.0);
+    assert_eq!{impossible.par_update_set([20].iter()).err(), Some(SuiteError::ImpossibleData)};
+}
@@ -0,0 +1,45 @@
+// This file contains code for use with "Think Bayes",
+// by Allen B. Downey, available from greenteapress.com
+//
+// Copyright 2013 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+extern crate think_bayes;
+use think_bayes::suite::*;
+use think_bayes::pdf::*;
+use think_bayes::kde::*;
+use think_bayes::gaussian::*;
+use think_bayes::real::*;
+use think_bayes::utils::*;
+#[macro_use]
+extern crate approx;
+
+// The book uses the showcases from two seasons of The Price is Right,
+// this is a small made up sample instead.
+const PRICES: [f64; 12] = [26000.0, 27500.0, 29000.0, 31200.0, 32500.0, 33100.0, 35000.0, 36200.0,
+                           38800.0, 41000.0, 44500.0, 52000.0];
+
+#[test]
+fn suite_price_is_right() {
+    // 6.2 - 6.4  The prior, from a kernel density estimate of past prices
+    let pdf_price = EstimatedPdf::new(&PRICES, Kernel::Gaussian, Bandwidth::Scott);
+    let prior = pdf_price.make_pmf(&linspace(0.0, 75000.0, 101));
+    assert_relative_eq!{prior.mean(), 35566.66611681574, max_relative = 1e-12};
+
+    // 6.5 - 6.7  Modeling the contestants, with normal errors
+    let pdf_error = Gaussian::new(0.0, 6000.0);
+    let mut posterior = FnSuite::new(prior.clone(),
+                                     |&guess: &f64, price: &Real| pdf_error.density(price.get() - guess));
+    let evidence = posterior.update(&20000.0);
+    assert_relative_eq!{evidence, 1.2992214204619308e-05, max_relative = 1e-12};
+    assert_relative_eq!{posterior.get_pmf().mean(), 26058.39085206574, max_relative = 1e-12};
+    assert_eq!{posterior.get_pmf().maximum_likelihood(), &Real::new(25500.0)};
+
+    // a less accurate guess moves the posterior less far from the prior toward the guess
+    let mut unsure = FnSuite::new(prior,
+                                  |&guess: &f64, price: &Real| eval_normal_pdf(price.get(), guess, 12000.0));
+    unsure.update(&20000.0);
+    assert!{unsure.get_pmf().mean() > posterior.get_pmf().mean()};
+}
@@ -0,0 +1,36 @@
+// This file contains code for use with "Think Bayes",
+// by Allen B. Downey, available from greenteapress.com
+//
+// Copyright 2012 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+extern crate think_bayes;
+extern crate rand;
+use think_bayes::dirichlet::*;
+use rand::{SeedableRng, XorShiftRng};
+#[macro_use]
+extern crate approx;
+
+#[test]
+fn dirichlet_lions_tigers_bears() {
+    // 15.2  Lions and tigers and bears
+    let mut dirichlet = Dirichlet::new(3, 1.0);
+    dirichlet.update(&[3, 2, 1]);
+    let expected = [4.0 / 9.0, 3.0 / 9.0, 2.0 / 9.0];
+    for (i, &mean) in expected.iter().enumerate() {
+        let marginal = dirichlet.marginal_pmf(i, 1001);
+        assert_relative_eq!{marginal.mean(), mean, max_relative = 1e-4};
+        assert_relative_eq!{dirichlet.predictive_pmf().prob(&i, 0.0), mean, max_relative = 1e-12};
+    }
+
+    // the likelihood of seeing one of each is random, but repeatable with a seed
+    let mut rng = XorShiftRng::from_seed([15, 2, 3, 1]);
+    let likes: Vec<f64> = (0..1000).map(|_| dirichlet.likelihood(&[1, 1, 1], &mut rng)).collect();
+    let mean = likes.iter().sum::<f64>() / likes.len() as f64;
+    // E[p1 p2 p3] = (4 * 3 * 2) / (9 * 10 * 11)
+    assert_relative_eq!{mean, 24.0 / 990.0, max_relative = 0.05};
+    let mut rng = XorShiftRng::from_seed([15, 2, 3, 1]);
+    assert_eq!{dirichlet.likelihood(&[1, 1, 1], &mut rng), likes[0]};
+}
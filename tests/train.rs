@@ -36,10 +36,16 @@ impl Train {
     }
 }
 
-impl Suite<u32, u32> for Train {
+impl HasPmf<u32> for Train {
+    fn get_pmf(&self) -> &Pmf<u32> {
+        &self.pmf
+    }
     fn get_mut_pmf(&mut self) -> &mut Pmf<u32> {
         &mut self.pmf
     }
+}
+
+impl Suite<u32, u32> for Train {
     fn likelihood(&self, data: &u32, hypo: &u32) -> f64 {
         if hypo < data {
             0.0
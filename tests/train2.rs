@@ -38,10 +38,16 @@ impl Train {
     }
 }
 
-impl Suite<u32, u32> for Train {
+impl HasPmf<u32> for Train {
+    fn get_pmf(&self) -> &Pmf<u32> {
+        &self.pmf
+    }
     fn get_mut_pmf(&mut self) -> &mut Pmf<u32> {
         &mut self.pmf
     }
+}
+
+impl Suite<u32, u32> for Train {
     fn likelihood(&self, data: &u32, hypo: &u32) -> f64 {
         if hypo < data {
             0.0
@@ -72,6 +78,10 @@ fn suite_train_power_law() {
     let cdf: Cdf<_> = suite.get_pdf().into();
     assert_eq!{cdf.percentile(5.0), 91}
     assert_eq!{cdf.percentile(95.0), 242}
+    // the highest probability interval is shifted toward the mode at 90
+    assert_eq!{suite.get_pdf().credible_interval(90.0), (&91, &242)}
+    assert_eq!{suite.get_pdf().max_like_interval(90.0), (&90, &192)}
+    assert_eq!{suite.get_pdf().max_like_set(90.0).len(), 103}
 
     let mut suite = Train::new(1..501, 1.0);
     suite.update(&60);
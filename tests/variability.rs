@@ -0,0 +1,68 @@
+// This file contains code for use with "Think Bayes",
+// by Allen B. Downey, available from greenteapress.com
+//
+// Copyright 2012 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+extern crate think_bayes;
+use think_bayes::pmf::*;
+use think_bayes::suite::*;
+use think_bayes::grid::*;
+use think_bayes::real::*;
+use think_bayes::utils::*;
+use think_bayes::gaussian::*;
+#[macro_use]
+extern crate approx;
+
+// The book uses heights from the BRFSS, this is a small made up sample instead.
+const HEIGHTS: [f64; 12] = [178.2, 171.5, 180.9, 175.0, 169.8, 183.4, 176.6, 172.1, 179.3, 174.4,
+                            186.0, 177.7];
+
+#[test]
+fn suite_variability_grid() {
+    // 10.1 - 10.3  The variability hypothesis, mean and standard deviation
+    let mus = linspace(165.0, 190.0, 51);
+    let sigmas = linspace(2.0, 12.0, 41);
+    let mut suite = GridSuite::new(vec![mus.clone(), sigmas.clone()],
+                                   |&x: &f64, params: &[f64]| eval_normal_pdf(x, params[0], params[1]));
+    let stats = suite.update_set(HEIGHTS.iter()).unwrap();
+
+    // the same model as a Pmf over the indexes of the grid
+    let mut prior = Pmf::new();
+    for i in 0..mus.len() {
+        for j in 0..sigmas.len() {
+            prior.set((i, j), 1.0);
+        }
+    }
+    prior.normalize(1.0);
+    let mut slow = FnSuite::new(prior,
+                                |&x: &f64, &(i, j): &(usize, usize)| eval_normal_pdf(x, mus[i], sigmas[j]));
+    let slow_stats = slow.update_set(HEIGHTS.iter()).unwrap();
+    assert_relative_eq!{stats.log_evidence, slow_stats.log_evidence, max_relative = 1e-12};
+    assert_relative_eq!{suite.prob(&[20, 10]), slow.get_pmf().prob(&(20, 10), 0.0), max_relative = 1e-9};
+
+    let mean = HEIGHTS.iter().sum::<f64>() / HEIGHTS.len() as f64;
+    assert_relative_eq!{suite.mean(0), mean, max_relative = 1e-3};
+    let mu = suite.marginal(0);
+    assert_relative_eq!{mu.mean(), suite.mean(0), max_relative = 1e-12};
+    let (low, high) = mu.credible_interval(90.0);
+    assert!{low.get() < mean && mean < high.get()};
+    let sigma = suite.marginal(1);
+    assert!{sigma.maximum_likelihood().get() > 3.0 && sigma.maximum_likelihood().get() < 6.0};
+    assert_eq!{suite.means().len(), 2};
+
+    // the joint posterior of mu and sigma
+    let joint = suite.joint(0, 1);
+    let slow_sigma = slow.get_pmf().items().into_iter().fold(Pmf::new(), |mut pmf, ((_, j), p)| {
+        pmf.incr(j, p);
+        pmf
+    });
+    assert_relative_eq!{joint.marginal::<1>().prob(&Real::new(sigmas[8]), 0.0),
+                        slow_sigma.prob(&8, 0.0),
+                        max_relative = 1e-9};
+    let region = joint.max_like_interval(50.0);
+    assert!{region.contains(&&(Real::new(suite.maximum_likelihood()[0]),
+                                Real::new(suite.maximum_likelihood()[1])))};
+}
@@ -0,0 +1,98 @@
+// This file contains code for use with "Think Stats" and
+// "Think Bayes", both by Allen B. Downey, available from greenteapress.com
+// Copyright 2014 Allen B. Downey
+//
+// Rewrite 2016 by Jacob Finkelman
+// License: GNU GPLv3 http://www.gnu.org/licenses/gpl.html
+
+// `#[derive(HasPmf)]` for think_bayes, use it through the "derive" feature of think_bayes.
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use syn::{Data, DeriveInput, Error, Fields, GenericArgument, Index, Member, PathArguments, Type};
+
+/// Implements `HasPmf` for a struct composed around a Pmf,
+/// so that only `likelihood` is left to write to implement `Suite`.
+///
+/// The Pmf field is marked with `#[pmf]`:
+///
+/// ```ignore
+/// #[derive(HasPmf)]
+/// struct Dice {
+///     #[pmf]
+///     pmf: Pmf<u32>,
+/// }
+///
+/// impl Suite<u32, u32> for Dice {
+///     fn likelihood(&self, data: &u32, hypo: &u32) -> f64 {
+///         if hypo < data { 0.0 } else { 1.0 / (*hypo as f64) }
+///     }
+/// }
+/// ```
+#[proc_macro_derive(HasPmf, attributes(pmf))]
+pub fn derive_has_pmf(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(Error::new(Span::call_site(),
+                                  "#[derive(HasPmf)] only works on structs"))
+        }
+    };
+    let mut marked = fields.iter()
+        .enumerate()
+        .filter(|&(_, f)| f.attrs.iter().any(|a| a.path().is_ident("pmf")));
+    let (index, field) = match (marked.next(), marked.next()) {
+        (Some(found), None) => found,
+        (None, _) => {
+            return Err(Error::new(Span::call_site(),
+                                  "#[derive(HasPmf)] needs a field marked #[pmf]"))
+        }
+        (Some(_), Some((_, second))) => {
+            return Err(Error::new_spanned(second, "only one field can be marked #[pmf]"))
+        }
+    };
+    let member = match *fields {
+        Fields::Named(_) => Member::Named(field.ident.clone().unwrap()),
+        _ => Member::Unnamed(Index::from(index)),
+    };
+    let value = hypothesis_type(&field.ty)?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::think_bayes::HasPmf<#value> for #name #ty_generics #where_clause {
+            fn get_pmf(&self) -> &::think_bayes::Pmf<#value> {
+                &self.#member
+            }
+            fn get_mut_pmf(&mut self) -> &mut ::think_bayes::Pmf<#value> {
+                &mut self.#member
+            }
+        }
+    })
+}
+
+/// Finds `V` in a field of type `Pmf<V>`.
+fn hypothesis_type(ty: &Type) -> Result<&Type, Error> {
+    if let Type::Path(ref path) = *ty {
+        if let Some(last) = path.path.segments.last() {
+            if last.ident == "Pmf" {
+                if let PathArguments::AngleBracketed(ref args) = last.arguments {
+                    if let Some(GenericArgument::Type(value)) = args.args.first() {
+                        return Ok(value);
+                    }
+                }
+            }
+        }
+    }
+    Err(Error::new_spanned(ty, "the field marked #[pmf] must be a Pmf<V>"))
+}